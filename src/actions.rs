@@ -0,0 +1,233 @@
+use super::card_data::CardDatabase;
+use super::combat;
+use super::effects;
+use super::entities::{Board, CardHolder, HeroClass, Target, TargetType};
+use super::match_cycle;
+use super::match_log;
+use super::stack::{self, EffectStack};
+use super::triggers;
+use bracket_random::prelude::RandomNumberGenerator;
+use specs::prelude::*;
+use std::error::Error;
+use std::fmt;
+
+/// Everything a player can do while it is their turn.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Action {
+    /// Plays the card at this hand index. Monsters need a friendly slot as target,
+    /// magic cards only need one if they have an effect on a chosen target.
+    PlayCard {
+        card: usize,
+        target: Option<Target>,
+    },
+    /// Attacks with the monster in this field slot.
+    Attack {
+        slot: usize,
+        target: TargetType,
+    },
+    /// Attacks with the hero's weapon, once per turn.
+    HeroAttack {
+        target: TargetType,
+    },
+    /// Moves the monster in `slot` to the friendly slot `to` for `MOVE_COST`, trading
+    /// places with the monster already there. Each monster moves once per turn, and
+    /// one that gets swapped counts as having moved.
+    Move {
+        slot: usize,
+        to: usize,
+    },
+    /// Uses the hero power, once per turn. Takes a target when the power has an
+    /// effect on a chosen target, like a magic card.
+    HeroPower {
+        target: Option<Target>,
+    },
+    EndTurn,
+    /// Lets the card on top of the stack go ahead without answering it.
+    Pass,
+    /// Discards the card at this hand index, towards the discards an effect asked for.
+    Discard {
+        card: usize,
+    },
+}
+
+/// Why the rules turned an action down.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ActionError {
+    MatchOver,
+    /// Someone else is to act, or the turn is not in its InPlay phase.
+    NotYourTurn,
+    UnknownPlayer(u8),
+    /// Cards on the stack have to be answered or passed first.
+    StackNotEmpty,
+    /// There is nothing on the stack to answer or pass.
+    StackEmpty,
+    /// Only instants can be played while the stack holds cards.
+    NotInstant,
+    CardNotInHand(usize),
+    NotEnoughResources {
+        cost: i32,
+        available: i32,
+    },
+    SlotOccupied(usize),
+    NoFreeSlot,
+    InvalidTarget(Target),
+    TargetRequired,
+    /// The card is played without picking a target.
+    TargetNotAllowed,
+    NoMonster(usize),
+    /// The monster is exhausted or frozen.
+    CannotAttack(usize),
+    /// The monster already moved this turn or is frozen.
+    CannotMove(usize),
+    OutOfRange,
+    TargetStealthed,
+    /// A Guard monster has to be attacked first.
+    GuardInTheWay,
+    /// The hero power was already used this turn.
+    PowerUsed,
+    /// The hero has no weapon to attack with.
+    NoWeapon,
+    /// The hero already attacked this turn.
+    HeroCannotAttack,
+    /// The card pool has no power for this class.
+    NoHeroPower(HeroClass),
+    /// The player already decided on their opening hand.
+    HandKept,
+    /// Cards have to be discarded before anything else.
+    MustDiscard,
+    NothingToDiscard,
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActionError::MatchOver => write!(f, "the match is over"),
+            ActionError::NotYourTurn => write!(f, "it is not your turn to act"),
+            ActionError::UnknownPlayer(player) => write!(f, "there is no player {}", player),
+            ActionError::StackNotEmpty => write!(f, "answer or pass the stack first"),
+            ActionError::StackEmpty => write!(f, "there is nothing on the stack"),
+            ActionError::NotInstant => write!(f, "only instants can answer the stack"),
+            ActionError::CardNotInHand(card) => write!(f, "no card {} in hand", card),
+            ActionError::NotEnoughResources { cost, available } => {
+                write!(f, "costs {} mana, only {} left", cost, available)
+            }
+            ActionError::SlotOccupied(slot) => write!(f, "slot {} is taken", slot),
+            ActionError::NoFreeSlot => write!(f, "the field is full"),
+            ActionError::InvalidTarget(_) => write!(f, "that can't be targeted"),
+            ActionError::TargetRequired => write!(f, "pick a target"),
+            ActionError::TargetNotAllowed => write!(f, "this card takes no target"),
+            ActionError::NoMonster(slot) => write!(f, "no monster in slot {}", slot),
+            ActionError::CannotAttack(slot) => {
+                write!(f, "the monster in slot {} can't attack", slot)
+            }
+            ActionError::CannotMove(slot) => {
+                write!(f, "the monster in slot {} can't move", slot)
+            }
+            ActionError::OutOfRange => write!(f, "the target is out of range"),
+            ActionError::TargetStealthed => write!(f, "stealthed monsters can't be attacked"),
+            ActionError::GuardInTheWay => write!(f, "a Guard monster is in the way"),
+            ActionError::PowerUsed => write!(f, "the hero power was used this turn"),
+            ActionError::NoWeapon => write!(f, "the hero has no weapon"),
+            ActionError::HeroCannotAttack => write!(f, "the hero already attacked this turn"),
+            ActionError::NoHeroPower(class) => {
+                write!(f, "the {} hero has no power", class.name())
+            }
+            ActionError::HandKept => write!(f, "the opening hand was already kept"),
+            ActionError::MustDiscard => write!(f, "discard a card first"),
+            ActionError::NothingToDiscard => write!(f, "there is nothing to discard"),
+        }
+    }
+}
+
+impl Error for ActionError {}
+
+/// Carries out `action` for `player`, or says why the rules don't allow it. While
+/// cards wait on the stack the player holding priority can only play instants or pass,
+/// and a player who owes discards can only discard. Whatever the action revealed goes
+/// into the match log, and a hero it killed ends the match right away.
+pub fn perform(ecs: &mut World, player: u8, action: Action) -> Result<(), ActionError> {
+    let result = perform_action(ecs, player, action);
+    match_log::collect(ecs);
+    match_cycle::update_match_status(ecs);
+    result
+}
+
+fn perform_action(ecs: &mut World, player: u8, action: Action) -> Result<(), ActionError> {
+    match_cycle::check_can_act(ecs, player)?;
+    let responding = !ecs.fetch::<EffectStack>().is_empty();
+    let discarding = match_cycle::discarding_player(ecs) == Some(player);
+
+    match action {
+        Action::Discard { card } => with_boards(ecs, player, |own, _, _, _| own.discard(card)),
+        _ if discarding => Err(ActionError::MustDiscard),
+        Action::PlayCard { card, target } if holds_magic(ecs, player, card) => {
+            stack::cast(ecs, player, card, target)
+        }
+        Action::Pass => stack::pass(ecs, player),
+        _ if responding => Err(ActionError::StackNotEmpty),
+        Action::PlayCard { card, target } => with_boards(ecs, player, |own, opponent, _, _| {
+            own.play_card(opponent, card, target).map(|_| ())
+        }),
+        Action::Attack { slot, target } => with_boards(ecs, player, |own, opponent, db, _| {
+            combat::attack(own, opponent, slot, target, db.matchups())
+        }),
+        Action::Move { slot, to } => {
+            with_boards(ecs, player, |own, _, _, _| own.move_monster(slot, to))
+        }
+        Action::HeroAttack { target } => with_boards(ecs, player, |own, opponent, db, _| {
+            combat::hero_attack(own, opponent, target, db.matchups())
+        }),
+        Action::HeroPower { target } => with_boards(ecs, player, |own, opponent, db, rng| {
+            hero_power(own, opponent, target, db, rng)
+        }),
+        Action::EndTurn => match_cycle::end_turn(ecs, player),
+    }
+}
+
+/// Uses the power of `own`'s hero at `target`. Powers don't go on the stack, they take
+/// effect right away like a monster being played.
+fn hero_power(
+    own: &mut Board,
+    opponent: &mut Board,
+    target: Option<Target>,
+    db: &CardDatabase,
+    rng: &mut RandomNumberGenerator,
+) -> Result<(), ActionError> {
+    let class = own.hero().class();
+    let power = db
+        .hero_power(class)
+        .ok_or(ActionError::NoHeroPower(class))?;
+    own.use_hero_power(opponent, &power, target)?;
+    effects::resolve_magic(&power, target, own, opponent, db, rng);
+    Ok(())
+}
+
+fn holds_magic(ecs: &World, player: u8, card: usize) -> bool {
+    let boards = ecs.read_storage::<Board>();
+    let board = (&boards).join().find(|b| b.id() == player);
+    matches!(
+        board.and_then(|b| b.hand().get(card)),
+        Some(CardHolder::MagicCard(_))
+    )
+}
+
+/// Runs `f` on the board of `player` and the opposing board, then resolves whatever
+/// abilities it set off.
+fn with_boards<F>(ecs: &World, player: u8, f: F) -> Result<(), ActionError>
+where
+    F: FnOnce(
+        &mut Board,
+        &mut Board,
+        &CardDatabase,
+        &mut RandomNumberGenerator,
+    ) -> Result<(), ActionError>,
+{
+    let db = ecs.fetch::<CardDatabase>();
+    let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+    let mut boards = ecs.write_storage::<Board>();
+    let (own, opponent) =
+        match_cycle::split_boards(&mut boards, player).ok_or(ActionError::UnknownPlayer(player))?;
+    f(own, opponent, &db, &mut rng)?;
+    triggers::resolve_pending(own, opponent, &db, &mut rng);
+    Ok(())
+}
@@ -0,0 +1,608 @@
+use super::actions::{self, Action, ActionError};
+use super::card_data::CardDatabase;
+use super::combat;
+use super::entities::{
+    Board, CardHolder, EffectKind, Hero, Magic, ModifierDuration, Monster, ResourcePool, Side,
+    Target, TargetSelector, TargetType, Weapon, MOVE_COST,
+};
+use super::match_cycle;
+use super::matchups::MatchupTable;
+use super::mulligan::{self, Mulligan};
+use super::stack::{EffectStack, StackEntry};
+use bracket_random::prelude::RandomNumberGenerator;
+use specs::prelude::*;
+
+/// Opening hand cards costing more than this go back in the mulligan.
+const MULLIGAN_MAX_COST: i32 = 4;
+
+/// Upper bound on the actions the computer takes in one turn, in case a rule change
+/// makes it keep retrying something that never goes through.
+const MAX_ACTIONS_PER_TURN: usize = 64;
+
+/// What one player is allowed to know about the match: their own hand and hero power,
+/// both fields and heroes, but only the size of the opponent's hand and of either
+/// deck. The school matchup table, both graveyards and the cards on the stack are
+/// public knowledge.
+pub struct PlayerView {
+    player: u8,
+    hand: Vec<CardHolder>,
+    field: [Option<Monster>; 5],
+    hero: Hero,
+    hero_power: Option<Magic>,
+    resources: ResourcePool,
+    deck_size: usize,
+    graveyard: Vec<CardHolder>,
+    discards: u32,
+    opponent_field: [Option<Monster>; 5],
+    opponent_hero: Hero,
+    opponent_hand_size: usize,
+    opponent_deck_size: usize,
+    opponent_graveyard: Vec<CardHolder>,
+    matchups: MatchupTable,
+    stack: Vec<StackEntry>,
+}
+
+impl PlayerView {
+    pub fn new(
+        own: &Board,
+        opponent: &Board,
+        db: &CardDatabase,
+        stack: &EffectStack,
+    ) -> PlayerView {
+        PlayerView {
+            player: own.id(),
+            hand: own.hand().clone(),
+            field: own.field(),
+            hero: own.hero().clone(),
+            hero_power: db.hero_power(own.hero().class()),
+            resources: own.resources(),
+            deck_size: own.count_deck_size(),
+            graveyard: own.graveyard().to_vec(),
+            discards: own.discards_pending(),
+            opponent_field: opponent.field(),
+            opponent_hero: opponent.hero().clone(),
+            opponent_hand_size: opponent.hand().len(),
+            opponent_deck_size: opponent.count_deck_size(),
+            opponent_graveyard: opponent.graveyard().to_vec(),
+            matchups: db.matchups().clone(),
+            stack: stack.entries().to_vec(),
+        }
+    }
+
+    /// The view `player` has of the match in `ecs`.
+    pub fn of(ecs: &World, player: u8) -> Option<PlayerView> {
+        let boards = ecs.read_storage::<Board>();
+        let db = ecs.fetch::<CardDatabase>();
+        let stack = ecs.fetch::<EffectStack>();
+        let own = (&boards).join().find(|b| b.id() == player)?;
+        let opponent = (&boards).join().find(|b| b.id() != player)?;
+        Some(PlayerView::new(own, opponent, &db, &stack))
+    }
+
+    pub fn hand(&self) -> &[CardHolder] {
+        &self.hand
+    }
+
+    pub fn hero(&self) -> &Hero {
+        &self.hero
+    }
+
+    pub fn hero_power(&self) -> Option<&Magic> {
+        self.hero_power.as_ref()
+    }
+
+    pub fn resources(&self) -> ResourcePool {
+        self.resources
+    }
+
+    pub fn deck_size(&self) -> usize {
+        self.deck_size
+    }
+
+    pub fn opponent_hero(&self) -> &Hero {
+        &self.opponent_hero
+    }
+
+    pub fn opponent_hand_size(&self) -> usize {
+        self.opponent_hand_size
+    }
+
+    pub fn opponent_deck_size(&self) -> usize {
+        self.opponent_deck_size
+    }
+
+    pub fn graveyard(&self) -> &[CardHolder] {
+        &self.graveyard
+    }
+
+    pub fn opponent_graveyard(&self) -> &[CardHolder] {
+        &self.opponent_graveyard
+    }
+
+    /// How many cards the player still has to discard.
+    pub fn discards(&self) -> u32 {
+        self.discards
+    }
+
+    fn can_resurrect(&self) -> bool {
+        self.free_slot().is_some()
+            && self
+                .graveyard
+                .iter()
+                .any(|c| matches!(c, CardHolder::MonsterCard(_)))
+    }
+
+    /// The cards waiting to resolve, bottom first.
+    pub fn stack(&self) -> &[StackEntry] {
+        &self.stack
+    }
+
+    fn free_slot(&self) -> Option<usize> {
+        self.field.iter().position(|m| m.is_none())
+    }
+
+    fn monsters(&self, side: Side) -> impl Iterator<Item = (usize, &Monster)> {
+        let field = match side {
+            Side::Friendly => &self.field,
+            Side::Enemy => &self.opponent_field,
+        };
+        field
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, m)| m.as_ref().map(|m| (slot, m)))
+    }
+}
+
+/// How much the computer would like to be rid of an enemy monster.
+fn threat(monster: &Monster) -> i32 {
+    monster.damage() * 2 + monster.health()
+}
+
+/// Picks a target for the chosen-target effects of `magic`, or None if casting it
+/// now would be wasted.
+fn magic_target(view: &PlayerView, magic: &Magic) -> Option<Option<Target>> {
+    for effect in magic.effects() {
+        let worthwhile = match (effect.kind(), effect.target()) {
+            (_, TargetSelector::Chosen) => true,
+            // Answers are only played from `choose_response`.
+            (_, TargetSelector::Responded) => false,
+            (EffectKind::DealDamage(_), TargetSelector::AllMonsters(Side::Enemy))
+            | (EffectKind::DamagePerGraveyardCard(_), TargetSelector::AllMonsters(Side::Enemy))
+            | (EffectKind::Destroy, TargetSelector::AllMonsters(Side::Enemy))
+            | (EffectKind::Silence, TargetSelector::AllMonsters(Side::Enemy)) => {
+                view.monsters(Side::Enemy).next().is_some()
+            }
+            (EffectKind::Buff { .. }, TargetSelector::AllMonsters(Side::Friendly))
+            | (EffectKind::BuffThisTurn { .. }, TargetSelector::AllMonsters(Side::Friendly))
+            | (EffectKind::Heal(_), TargetSelector::AllMonsters(Side::Friendly)) => {
+                view.monsters(Side::Friendly).next().is_some()
+            }
+            (_, TargetSelector::RandomMonster(side)) => view.monsters(side).next().is_some(),
+            (EffectKind::Summon(_), _) => view.free_slot().is_some(),
+            (EffectKind::Resurrect, _) => view.can_resurrect(),
+            (EffectKind::ReturnToHand, TargetSelector::Hero(Side::Friendly)) => {
+                !view.graveyard().is_empty()
+            }
+            (EffectKind::Heal(_), TargetSelector::Hero(Side::Friendly)) => {
+                view.hero().health() < view.hero().base_health()
+            }
+            (EffectKind::DealDamage(amount), TargetSelector::Hero(Side::Friendly)) => {
+                view.hero().health() + view.hero().armor() > amount * 5
+            }
+            (EffectKind::Draw(_), _) => view.deck_size() > 0,
+            (EffectKind::Discard(_), TargetSelector::Hero(Side::Enemy))
+            | (EffectKind::DiscardRandom(_), TargetSelector::Hero(Side::Enemy)) => {
+                view.opponent_hand_size() > 0
+            }
+            _ => true,
+        };
+        if !worthwhile {
+            return None;
+        }
+    }
+    if !magic.needs_target() {
+        return Some(None);
+    }
+
+    let chosen = magic
+        .effects()
+        .iter()
+        .find(|e| e.target() == TargetSelector::Chosen)?;
+    let target = match chosen.kind() {
+        EffectKind::DealDamage(amount) => damage_target(view, magic, *amount)?,
+        EffectKind::DamagePerGraveyardCard(per_card) => {
+            damage_target(view, magic, per_card * view.graveyard().len() as i32)?
+        }
+        EffectKind::Destroy | EffectKind::Exile | EffectKind::ReturnToHand => view
+            .monsters(Side::Enemy)
+            .filter(|(_, m)| !m.is_stealthed())
+            .max_by_key(|(_, m)| threat(m))
+            .map(|(slot, _)| Target::Enemy(TargetType::Monster(slot)))?,
+        EffectKind::Heal(_) => {
+            if view.hero().health() < view.hero().base_health() {
+                Target::Friendly(TargetType::Hero)
+            } else {
+                return None;
+            }
+        }
+        EffectKind::Buff { .. } => view
+            .monsters(Side::Friendly)
+            .max_by_key(|(_, m)| m.damage())
+            .map(|(slot, _)| Target::Friendly(TargetType::Monster(slot)))?,
+        EffectKind::BuffThisTurn { .. } => view
+            .monsters(Side::Friendly)
+            .filter(|(_, m)| m.can_attack())
+            .max_by_key(|(_, m)| m.damage())
+            .map(|(slot, _)| Target::Friendly(TargetType::Monster(slot)))?,
+        EffectKind::SetHealth(health) => {
+            set_stat_target(view, |m| m.health() - health, |m| health - m.health())?
+        }
+        EffectKind::SetDamage(damage) => {
+            set_stat_target(view, |m| m.damage() - damage, |m| damage - m.damage())?
+        }
+        EffectKind::Splash(_) => splash_target(view)?,
+        EffectKind::Silence => view
+            .monsters(Side::Enemy)
+            .filter(|(_, m)| !m.is_stealthed() && has_something_to_silence(m))
+            .max_by_key(|(_, m)| threat(m))
+            .map(|(slot, _)| Target::Enemy(TargetType::Monster(slot)))?,
+        EffectKind::Summon(_) => Target::Friendly(TargetType::Monster(view.free_slot()?)),
+        EffectKind::Draw(_) | EffectKind::GainArmor(_) => Target::Friendly(TargetType::Hero),
+        EffectKind::Resurrect if view.can_resurrect() => {
+            Target::Friendly(TargetType::Monster(view.free_slot()?))
+        }
+        EffectKind::Discard(_) | EffectKind::DiscardRandom(_) if view.opponent_hand_size() > 0 => {
+            Target::Enemy(TargetType::Hero)
+        }
+        EffectKind::Resurrect
+        | EffectKind::Counter
+        | EffectKind::Discard(_)
+        | EffectKind::DiscardRandom(_) => return None,
+    };
+    Some(Some(target))
+}
+
+/// The monster a "set to" effect does the most good on: the enemy that loses the most
+/// by it, or else the friendly monster that gains the most.
+fn set_stat_target(
+    view: &PlayerView,
+    enemy_loss: impl Fn(&Monster) -> i32,
+    friendly_gain: impl Fn(&Monster) -> i32,
+) -> Option<Target> {
+    let enemy = view
+        .monsters(Side::Enemy)
+        .filter(|(_, m)| !m.is_stealthed() && enemy_loss(m) > 0)
+        .max_by_key(|(_, m)| enemy_loss(m))
+        .map(|(slot, _)| Target::Enemy(TargetType::Monster(slot)));
+    enemy.or_else(|| {
+        view.monsters(Side::Friendly)
+            .filter(|(_, m)| friendly_gain(m) > 0)
+            .max_by_key(|(_, m)| friendly_gain(m))
+            .map(|(slot, _)| Target::Friendly(TargetType::Monster(slot)))
+    })
+}
+
+/// The enemy monster whose slot catches the most monsters in a splash, the biggest
+/// threats first.
+fn splash_target(view: &PlayerView) -> Option<Target> {
+    let enemies: Vec<(usize, &Monster)> = view.monsters(Side::Enemy).collect();
+    let caught = |slot: usize| {
+        let hit = enemies
+            .iter()
+            .filter(|(other, _)| other.abs_diff(slot) <= 1);
+        (
+            hit.clone().count(),
+            hit.map(|(_, m)| threat(m)).sum::<i32>(),
+        )
+    };
+    enemies
+        .iter()
+        .filter(|(_, m)| !m.is_stealthed())
+        .max_by_key(|(slot, _)| caught(*slot))
+        .map(|(slot, _)| Target::Enemy(TargetType::Monster(*slot)))
+}
+
+/// Whether silencing `monster` would take anything away from it.
+fn has_something_to_silence(monster: &Monster) -> bool {
+    monster
+        .modifiers()
+        .iter()
+        .any(|m| !matches!(m.duration(), ModifierDuration::Aura(_)))
+        || !monster.active_keywords().is_empty()
+        || !monster.abilities().is_empty()
+        || monster.aura().is_some()
+}
+
+/// The enemy monster `amount` damage of `magic` would kill, the biggest threat first,
+/// or else the enemy hero. None if the spell would deal no damage at all.
+fn damage_target(view: &PlayerView, magic: &Magic, amount: i32) -> Option<Target> {
+    if amount <= 0 {
+        return None;
+    }
+    let target = view
+        .monsters(Side::Enemy)
+        .filter(|(_, m)| !m.is_stealthed())
+        .filter(|(_, m)| m.health() <= view.matchups.damage_to_monster(amount, magic.school(), m))
+        .max_by_key(|(_, m)| threat(m))
+        .map(|(slot, _)| Target::Enemy(TargetType::Monster(slot)))
+        .unwrap_or(Target::Enemy(TargetType::Hero));
+    Some(target)
+}
+
+/// Whether `weapon` has more damage left in it than the one the hero holds.
+fn is_upgrade(view: &PlayerView, weapon: &Weapon) -> bool {
+    let value = |w: &Weapon| w.attack() * w.durability();
+    view.hero()
+        .weapon()
+        .is_none_or(|held| value(held) < value(weapon))
+}
+
+/// The best card to play right now, most expensive first.
+fn choose_card(
+    view: &PlayerView,
+    rejected: &[Action],
+    rng: &mut RandomNumberGenerator,
+) -> Option<Action> {
+    let mut playable: Vec<(usize, &CardHolder)> = view
+        .hand()
+        .iter()
+        .enumerate()
+        .filter(|(_, card)| view.resources().can_pay(card.cost()))
+        .collect();
+    // Shuffled first, so there is no telling which of two cards of the same cost comes first.
+    for i in (1..playable.len()).rev() {
+        let j = rng.range(0, i + 1);
+        playable.swap(i, j);
+    }
+    playable.sort_by_key(|(_, card)| -card.cost());
+
+    playable.into_iter().find_map(|(card, holder)| {
+        let target = match holder {
+            CardHolder::MonsterCard(_) => {
+                Some(Target::Friendly(TargetType::Monster(view.free_slot()?)))
+            }
+            CardHolder::MagicCard(magic) => magic_target(view, magic)?,
+            CardHolder::WeaponCard(weapon) if is_upgrade(view, weapon) => None,
+            CardHolder::WeaponCard(_) => return None,
+        };
+        let action = Action::PlayCard { card, target };
+        if rejected.contains(&action) {
+            None
+        } else {
+            Some(action)
+        }
+    })
+}
+
+/// Uses the hero power if it is ready and would do any good.
+fn choose_power(view: &PlayerView, rejected: &[Action]) -> Option<Action> {
+    let power = view.hero_power()?;
+    if view.hero().power_used() || !view.resources().can_pay(power.cost()) {
+        return None;
+    }
+    let action = Action::HeroPower {
+        target: magic_target(view, power)?,
+    };
+    if rejected.contains(&action) {
+        None
+    } else {
+        Some(action)
+    }
+}
+
+/// An attack for the first monster that can still make one. Monsters trade with
+/// enemies they can kill and survive, and go for the hero otherwise. When a Guard
+/// is in the way they only attack it if they survive the blow back.
+fn choose_attack(view: &PlayerView, rejected: &[Action]) -> Option<Action> {
+    view.monsters(Side::Friendly).find_map(|(slot, attacker)| {
+        let legal = |target| combat::is_legal_attack(attacker, slot, &view.opponent_field, target);
+        let outcome = |target: usize, defender: &Monster| {
+            let retaliation = if combat::in_range(target, slot, defender.attack_type().range()) {
+                view.matchups
+                    .damage_to_monster(defender.damage(), defender.school(), attacker)
+            } else {
+                0
+            };
+            let damage =
+                view.matchups
+                    .damage_to_monster(attacker.damage(), attacker.school(), defender);
+            (defender.health() <= damage, retaliation < attacker.health())
+        };
+        let targets: Vec<(usize, &Monster)> = view
+            .monsters(Side::Enemy)
+            .filter(|(target, _)| legal(TargetType::Monster(*target)))
+            .collect();
+
+        let trade = targets
+            .iter()
+            .filter(|(target, defender)| outcome(*target, defender) == (true, true))
+            .max_by_key(|(_, defender)| threat(defender))
+            .map(|(target, _)| TargetType::Monster(*target));
+        let target = if trade.is_some() {
+            trade
+        } else if legal(TargetType::Hero) {
+            Some(TargetType::Hero)
+        } else {
+            targets
+                .iter()
+                .filter(|(target, defender)| outcome(*target, defender).1)
+                .min_by_key(|(_, defender)| defender.health())
+                .map(|(target, _)| TargetType::Monster(*target))
+        };
+
+        let action = Action::Attack {
+            slot,
+            target: target?,
+        };
+        if rejected.contains(&action) {
+            None
+        } else {
+            Some(action)
+        }
+    })
+}
+
+/// A move into a free slot for a monster that could attack but has nothing in reach,
+/// to the first slot from which it reaches an enemy monster.
+fn choose_move(view: &PlayerView, rejected: &[Action]) -> Option<Action> {
+    if !view.resources().can_pay(MOVE_COST) {
+        return None;
+    }
+    let reaches = |attacker: &Monster, from: usize| {
+        combat::is_legal_attack(attacker, from, &view.opponent_field, TargetType::Hero)
+            || view.monsters(Side::Enemy).any(|(target, _)| {
+                let target = TargetType::Monster(target);
+                combat::is_legal_attack(attacker, from, &view.opponent_field, target)
+            })
+    };
+    view.monsters(Side::Friendly)
+        .filter(|(slot, m)| m.can_attack() && m.can_move() && !reaches(m, *slot))
+        .find_map(|(slot, attacker)| {
+            (0..view.field.len())
+                .filter(|to| view.field[*to].is_none() && reaches(attacker, *to))
+                .map(|to| Action::Move { slot, to })
+                .find(|action| !rejected.contains(action))
+        })
+}
+
+/// An attack with the hero's weapon, trading with a monster it can kill without
+/// taking more than a quarter of the hero's health, or else going for the enemy hero.
+fn choose_hero_attack(view: &PlayerView, rejected: &[Action]) -> Option<Action> {
+    let weapon = view.hero().weapon()?;
+    let legal = |target| {
+        combat::check_hero_attack(view.hero(), &view.opponent_field, target).is_ok()
+            && !rejected.contains(&Action::HeroAttack { target })
+    };
+    let hero = view.hero();
+    let trade = view
+        .monsters(Side::Enemy)
+        .filter(|(slot, _)| legal(TargetType::Monster(*slot)))
+        .filter(|(slot, defender)| {
+            let damage =
+                view.matchups
+                    .damage_to_monster(weapon.attack(), weapon.school(), defender);
+            let retaliation =
+                if combat::in_range(*slot, combat::HERO_COLUMN, defender.attack_type().range()) {
+                    view.matchups
+                        .damage_to_hero(defender.damage(), defender.school(), hero)
+                } else {
+                    0
+                };
+            defender.health() <= damage && retaliation * 4 <= hero.health() + hero.armor()
+        })
+        .max_by_key(|(_, defender)| threat(defender))
+        .map(|(slot, _)| TargetType::Monster(slot));
+    let target = match trade {
+        Some(target) => target,
+        None if legal(TargetType::Hero) => TargetType::Hero,
+        None => return None,
+    };
+    Some(Action::HeroAttack { target })
+}
+
+/// Counters the card on top of the stack if the opponent played it and an answer
+/// can be paid for.
+fn choose_response(view: &PlayerView, rejected: &[Action]) -> Option<Action> {
+    if view.stack().last()?.controller() == view.player {
+        return None;
+    }
+    view.hand()
+        .iter()
+        .enumerate()
+        .filter(|(_, card)| match card {
+            CardHolder::MagicCard(magic) => magic.is_instant() && magic.is_response_only(),
+            CardHolder::MonsterCard(_) | CardHolder::WeaponCard(_) => false,
+        })
+        .filter(|(_, card)| view.resources().can_pay(card.cost()))
+        .map(|(card, _)| Action::PlayCard { card, target: None })
+        .find(|action| !rejected.contains(action))
+}
+
+/// The cheapest card in hand, while the computer owes discards.
+fn choose_discard(view: &PlayerView, rejected: &[Action]) -> Option<Action> {
+    if view.discards() == 0 {
+        return None;
+    }
+    view.hand()
+        .iter()
+        .enumerate()
+        .filter(|(card, _)| !rejected.contains(&Action::Discard { card: *card }))
+        .min_by_key(|(_, card)| card.cost())
+        .map(|(card, _)| Action::Discard { card })
+}
+
+/// The next thing the computer wants to do. Discards it owes come first. While the
+/// stack holds cards it answers or passes. Otherwise cards and then the hero power
+/// come before attacks so new buffs and removal are in place first, and the turn
+/// ends when nothing is left.
+pub fn choose_action(
+    view: &PlayerView,
+    rejected: &[Action],
+    rng: &mut RandomNumberGenerator,
+) -> Action {
+    if let Some(discard) = choose_discard(view, rejected) {
+        return discard;
+    }
+    if !view.stack().is_empty() {
+        return choose_response(view, rejected).unwrap_or(Action::Pass);
+    }
+    choose_card(view, rejected, rng)
+        .or_else(|| choose_power(view, rejected))
+        .or_else(|| choose_attack(view, rejected))
+        .or_else(|| choose_move(view, rejected))
+        .or_else(|| choose_hero_attack(view, rejected))
+        .unwrap_or(Action::EndTurn)
+}
+
+/// The cards of the opening hand to shuffle back: everything too expensive to play
+/// in the first turns.
+pub fn choose_mulligan(view: &PlayerView) -> Vec<usize> {
+    view.hand()
+        .iter()
+        .enumerate()
+        .filter(|(_, card)| card.cost() > MULLIGAN_MAX_COST)
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Decides on `player`'s opening hand, if they still have to.
+pub fn mulligan(ecs: &World, player: u8) {
+    if !ecs.fetch::<Mulligan>().is_waiting(player) {
+        return;
+    }
+    if let Some(view) = PlayerView::of(ecs, player) {
+        let _ = mulligan::replace(ecs, player, &choose_mulligan(&view));
+    }
+}
+
+/// Acts for `player` for as long as they may: their whole InPlay phase, or answering
+/// the stack while they hold priority. Decides only from what that player can see.
+/// Does nothing if they may not act.
+pub fn take_turn(ecs: &mut World, player: u8) {
+    let mut rejected = vec![];
+    for _ in 0..MAX_ACTIONS_PER_TURN {
+        if !match_cycle::can_act(ecs, player) {
+            return;
+        }
+        let view = match PlayerView::of(ecs, player) {
+            Some(view) => view,
+            None => return,
+        };
+        let action = {
+            let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+            choose_action(&view, &rejected, &mut rng)
+        };
+        match actions::perform(ecs, player, action) {
+            // Hand indices and slots have moved, so earlier refusals no longer apply.
+            Ok(()) => rejected.clear(),
+            Err(ActionError::MatchOver) | Err(ActionError::NotYourTurn) => return,
+            Err(_) => rejected.push(action),
+        }
+        if action == Action::EndTurn {
+            return;
+        }
+    }
+    while actions::perform(ecs, player, Action::Pass).is_ok() {}
+    // Either ends the turn or it wasn't the computer's to end.
+    let _ = actions::perform(ecs, player, Action::EndTurn);
+}
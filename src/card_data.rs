@@ -1,15 +1,461 @@
-use super::entities::{DamageType, MagicSchool, MonsterData};
-use MagicSchool::*;
+use super::entities::{
+    Ability, CardData, CardHolder, Effect, EffectKind, HeroClass, Magic, MagicData, Monster,
+    MonsterData, TargetSelector, Trigger, Weapon, WeaponData, HERO_CLASSES,
+};
+use super::matchups::MatchupTable;
+use serde::{Deserialize, Deserializer};
 use specs::prelude::*;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 
-pub fn initialize_card_data(ecs: &mut World) {
-    let mut monsters: Vec<MonsterData> = Vec::<MonsterData>::new();
+pub const CARD_DATA_DIR: &str = "data/cards";
 
-    let wisp = MonsterData::new(String::from("Wisp"), 1, 1, 1, DamageType::new(0, Flame));
-    let orc_grunt = MonsterData::new(String::from("Orc Grunt"), 3, 3, 3, DamageType::new(0, Physical));
+/// Every card definition available to a match, keyed by its card identifier.
+#[derive(Default)]
+pub struct CardDatabase {
+    monsters: HashMap<String, MonsterData>,
+    magic: HashMap<String, MagicData>,
+    weapons: HashMap<String, WeaponData>,
+    hero_powers: HashMap<HeroClass, MagicData>,
+    sources: HashMap<String, PathBuf>,
+    matchups: MatchupTable,
+    matchups_source: Option<PathBuf>,
+}
 
-    monsters.push(wisp);
-    monsters.push(orc_grunt);
+impl CardDatabase {
+    pub fn monster(&self, id: &str) -> Option<&MonsterData> {
+        self.monsters.get(id)
+    }
 
-    ecs.insert(monsters);
-}
\ No newline at end of file
+    pub fn magic(&self, id: &str) -> Option<&MagicData> {
+        self.magic.get(id)
+    }
+
+    pub fn weapon(&self, id: &str) -> Option<&WeaponData> {
+        self.weapons.get(id)
+    }
+
+    pub fn magics(&self) -> impl Iterator<Item = &MagicData> {
+        self.magic.values()
+    }
+
+    pub fn matchups(&self) -> &MatchupTable {
+        &self.matchups
+    }
+
+    /// Every card identifier in the pool, sorted. Hero powers aren't part of it.
+    pub fn card_ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self
+            .monsters
+            .keys()
+            .chain(self.magic.keys())
+            .chain(self.weapons.keys())
+            .map(|id| id.as_str())
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// The power of heroes of `class`, ready to be used.
+    pub fn hero_power(&self, class: HeroClass) -> Option<Magic> {
+        self.hero_powers
+            .get(&class)
+            .map(|data| Magic::new(0, data.clone()))
+    }
+
+    /// A new copy of the card `id`, numbered `card_id` within its deck.
+    pub fn card(&self, id: &str, card_id: u32) -> Option<CardHolder> {
+        if let Some(data) = self.monster(id) {
+            Some(CardHolder::MonsterCard(Monster::new(card_id, data.clone())))
+        } else if let Some(data) = self.magic(id) {
+            Some(CardHolder::MagicCard(Magic::new(card_id, data.clone())))
+        } else {
+            self.weapon(id)
+                .map(|data| CardHolder::WeaponCard(Weapon::new(card_id, data.clone())))
+        }
+    }
+
+    fn claim_id(&mut self, id: &str, path: &Path) -> Result<(), CardDataError> {
+        if id.trim().is_empty() {
+            return Err(CardDataError::Invalid {
+                path: path.to_path_buf(),
+                id: id.to_string(),
+                reason: "card identifier is empty".to_string(),
+            });
+        }
+        if let Some(first) = self.sources.get(id) {
+            return Err(CardDataError::Duplicate {
+                id: id.to_string(),
+                first: first.clone(),
+                second: path.to_path_buf(),
+            });
+        }
+        self.sources.insert(id.to_string(), path.to_path_buf());
+        Ok(())
+    }
+
+    fn add_monster(&mut self, data: MonsterData, path: &Path) -> Result<(), CardDataError> {
+        self.claim_id(data.id(), path)?;
+        let reason = if data.base_cost() < 0 {
+            Some("cost is negative")
+        } else if data.base_health() <= 0 {
+            Some("health must be positive")
+        } else if data.base_damage() < 0 {
+            Some("damage is negative")
+        } else {
+            None
+        };
+        if let Some(reason) = reason {
+            return Err(CardDataError::Invalid {
+                path: path.to_path_buf(),
+                id: data.id().to_string(),
+                reason: reason.to_string(),
+            });
+        }
+        self.monsters.insert(data.id().to_string(), data);
+        Ok(())
+    }
+
+    fn add_magic(&mut self, data: MagicData, path: &Path) -> Result<(), CardDataError> {
+        self.claim_id(data.id(), path)?;
+        let reason = if data.base_cost() < 0 {
+            Some("cost is negative")
+        } else if data.abilities().is_empty() != (data.lingers() == 0) {
+            Some("a card with abilities has to linger for some turns, and only then")
+        } else if data
+            .abilities()
+            .iter()
+            .any(|ability| !matches!(ability.trigger(), Trigger::Phase(_)))
+        {
+            Some("magic card abilities can only trigger at turn phases")
+        } else {
+            None
+        };
+        if let Some(reason) = reason {
+            return Err(CardDataError::Invalid {
+                path: path.to_path_buf(),
+                id: data.id().to_string(),
+                reason: reason.to_string(),
+            });
+        }
+        self.magic.insert(data.id().to_string(), data);
+        Ok(())
+    }
+
+    fn add_weapon(&mut self, data: WeaponData, path: &Path) -> Result<(), CardDataError> {
+        self.claim_id(data.id(), path)?;
+        let reason = if data.base_cost() < 0 {
+            Some("cost is negative")
+        } else if data.attack() < 0 {
+            Some("attack is negative")
+        } else if data.durability() <= 0 {
+            Some("durability must be positive")
+        } else {
+            None
+        };
+        if let Some(reason) = reason {
+            return Err(CardDataError::Invalid {
+                path: path.to_path_buf(),
+                id: data.id().to_string(),
+                reason: reason.to_string(),
+            });
+        }
+        self.weapons.insert(data.id().to_string(), data);
+        Ok(())
+    }
+
+    /// Hero powers are kept apart from the cards, so they never show up in the pool,
+    /// but share their identifiers.
+    fn add_hero_power(&mut self, data: MagicData, path: &Path) -> Result<(), CardDataError> {
+        self.claim_id(data.id(), path)?;
+        let invalid = |reason: &str| CardDataError::Invalid {
+            path: path.to_path_buf(),
+            id: data.id().to_string(),
+            reason: reason.to_string(),
+        };
+        let class = match data.class() {
+            Some(class) => class,
+            None => return Err(invalid("hero powers need the class they belong to")),
+        };
+        if data.base_cost() < 0 {
+            return Err(invalid("cost is negative"));
+        }
+        if data.is_instant() {
+            return Err(invalid("hero powers can't be instants"));
+        }
+        if !data.abilities().is_empty() {
+            return Err(invalid("hero powers can't linger in play"));
+        }
+        if self.hero_powers.contains_key(&class) {
+            return Err(invalid("this class already has a hero power"));
+        }
+        self.hero_powers.insert(class, data);
+        Ok(())
+    }
+
+    fn set_matchups(&mut self, table: MatchupTable, path: &Path) -> Result<(), CardDataError> {
+        if let Some(first) = &self.matchups_source {
+            return Err(CardDataError::Duplicate {
+                id: "matchups".to_string(),
+                first: first.clone(),
+                second: path.to_path_buf(),
+            });
+        }
+        if !table.is_valid() {
+            return Err(CardDataError::Invalid {
+                path: path.to_path_buf(),
+                id: "matchups".to_string(),
+                reason: "damage percentages can't be negative".to_string(),
+            });
+        }
+        self.matchups = table;
+        self.matchups_source = Some(path.to_path_buf());
+        Ok(())
+    }
+
+    /// Makes sure every card named by an effect exists, that set effects use sensible
+    /// values, and that every class has a hero power.
+    fn check_references(&self) -> Result<(), CardDataError> {
+        let invalid = |id: &str, reason: String| CardDataError::Invalid {
+            path: self.sources[id].clone(),
+            id: id.to_string(),
+            reason,
+        };
+        let check_effect = |id: &str, effect: &Effect| match effect.kind() {
+            EffectKind::Summon(summoned) if self.monster(summoned).is_none() => Err(invalid(
+                id,
+                format!("summons unknown monster \"{}\"", summoned),
+            )),
+            EffectKind::SetHealth(health) if *health <= 0 => Err(invalid(
+                id,
+                "health can only be set to a positive value".to_string(),
+            )),
+            EffectKind::SetDamage(damage) if *damage < 0 => {
+                Err(invalid(id, "damage can't be set below zero".to_string()))
+            }
+            _ => Ok(()),
+        };
+        let check_ability = |id: &str, ability: &Ability| {
+            check_effect(id, ability.effect())?;
+            if ability.effect().target() == TargetSelector::Chosen {
+                return Err(invalid(
+                    id,
+                    "abilities can't ask for a chosen target".to_string(),
+                ));
+            }
+            if ability.effect().target() == TargetSelector::Responded
+                || *ability.effect().kind() == EffectKind::Counter
+            {
+                return Err(invalid(
+                    id,
+                    "abilities can't answer cards on the stack".to_string(),
+                ));
+            }
+            Ok(())
+        };
+        if let Some(class) = HERO_CLASSES
+            .iter()
+            .find(|class| !self.hero_powers.contains_key(class))
+        {
+            return Err(CardDataError::MissingHeroPower(*class));
+        }
+        for magic in self.magic.values().chain(self.hero_powers.values()) {
+            for effect in magic.effects() {
+                check_effect(magic.id(), effect)?;
+                if effect.target() == TargetSelector::This {
+                    return Err(invalid(
+                        magic.id(),
+                        "only monster abilities can target the monster itself".to_string(),
+                    ));
+                }
+                if (*effect.kind() == EffectKind::Counter)
+                    != (effect.target() == TargetSelector::Responded)
+                {
+                    return Err(invalid(
+                        magic.id(),
+                        "Counter goes together with the Responded target".to_string(),
+                    ));
+                }
+                if effect.target() == TargetSelector::Responded && !magic.is_instant() {
+                    return Err(invalid(
+                        magic.id(),
+                        "only instants can answer another card".to_string(),
+                    ));
+                }
+            }
+        }
+        for magic in self.magic.values() {
+            for ability in magic.abilities() {
+                check_ability(magic.id(), ability)?;
+                if ability.effect().target() == TargetSelector::This {
+                    return Err(invalid(
+                        magic.id(),
+                        "only monster abilities can target the monster itself".to_string(),
+                    ));
+                }
+            }
+        }
+        for monster in self.monsters.values() {
+            for ability in monster.abilities() {
+                check_ability(monster.id(), ability)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Layout of a single card file. Every part may be left out, but only one file
+/// can hold the school matchup table.
+#[derive(Deserialize)]
+struct CardFile {
+    #[serde(default)]
+    monsters: Vec<MonsterData>,
+    #[serde(default)]
+    magic: Vec<MagicData>,
+    #[serde(default)]
+    weapons: Vec<WeaponData>,
+    #[serde(default)]
+    hero_powers: Vec<MagicData>,
+    #[serde(default, deserialize_with = "present")]
+    matchups: Option<MatchupTable>,
+}
+
+/// Lets an optional section or field be written without wrapping it in `Some(..)` in
+/// RON. Leaving it out is the only way to say None, so fields using this must skip
+/// serializing None.
+pub(crate) fn present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug)]
+pub enum CardDataError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        message: String,
+    },
+    Duplicate {
+        id: String,
+        first: PathBuf,
+        second: PathBuf,
+    },
+    Invalid {
+        path: PathBuf,
+        id: String,
+        reason: String,
+    },
+    MissingHeroPower(HeroClass),
+}
+
+impl fmt::Display for CardDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CardDataError::Io { path, source } => {
+                write!(f, "could not read {}: {}", path.display(), source)
+            }
+            CardDataError::Parse { path, message } => {
+                write!(f, "malformed card file {}: {}", path.display(), message)
+            }
+            CardDataError::Duplicate { id, first, second } => write!(
+                f,
+                "card \"{}\" is defined in both {} and {}",
+                id,
+                first.display(),
+                second.display()
+            ),
+            CardDataError::Invalid { path, id, reason } => {
+                write!(
+                    f,
+                    "invalid card \"{}\" in {}: {}",
+                    id,
+                    path.display(),
+                    reason
+                )
+            }
+            CardDataError::MissingHeroPower(class) => {
+                write!(f, "no hero power for the {} class", class.name())
+            }
+        }
+    }
+}
+
+impl Error for CardDataError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CardDataError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+fn parse_card_file(path: &Path) -> Result<CardFile, CardDataError> {
+    let contents = fs::read_to_string(path).map_err(|source| CardDataError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let parsed = match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => serde_json::from_str(&contents).map_err(|e| e.to_string()),
+        _ => ron::de::from_str(&contents).map_err(|e| e.to_string()),
+    };
+    parsed.map_err(|message| CardDataError::Parse {
+        path: path.to_path_buf(),
+        message,
+    })
+}
+
+/// Reads every `.ron` and `.json` file in `dir`, in file name order, into one database.
+pub fn load_card_database(dir: &Path) -> Result<CardDatabase, CardDataError> {
+    let io_error = |source| CardDataError::Io {
+        path: dir.to_path_buf(),
+        source,
+    };
+    let mut paths = vec![];
+    for entry in fs::read_dir(dir).map_err(io_error)? {
+        let path = entry.map_err(io_error)?.path();
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("ron") | Some("json") => paths.push(path),
+            _ => {}
+        }
+    }
+    paths.sort();
+
+    let mut database = CardDatabase::default();
+    for path in paths {
+        let file = parse_card_file(&path)?;
+        for monster in file.monsters {
+            database.add_monster(monster, &path)?;
+        }
+        for magic in file.magic {
+            database.add_magic(magic, &path)?;
+        }
+        for weapon in file.weapons {
+            database.add_weapon(weapon, &path)?;
+        }
+        for power in file.hero_powers {
+            database.add_hero_power(power, &path)?;
+        }
+        if let Some(table) = file.matchups {
+            database.set_matchups(table, &path)?;
+        }
+    }
+    database.check_references()?;
+
+    Ok(database)
+}
+
+pub fn initialize_card_data(ecs: &mut World) -> Result<(), CardDataError> {
+    let database = load_card_database(Path::new(CARD_DATA_DIR))?;
+    ecs.insert(database);
+    Ok(())
+}
@@ -0,0 +1,215 @@
+use super::actions::ActionError;
+use super::entities::{Board, CardHolder, Hero, Keyword, Monster, TargetType};
+use super::matchups::MatchupTable;
+use super::status;
+
+/// The column heroes attack from and are struck back at, behind the middle of the field.
+pub const HERO_COLUMN: usize = 2;
+
+/// A monster reaches every opposing column no further than its range from its own.
+pub fn in_range(from: usize, to: usize, range: u32) -> bool {
+    from.abs_diff(to) <= range as usize
+}
+
+/// Checks that `attacker`, standing in `slot`, may attack `target` on a field of
+/// `defenders`. Stealthed monsters can't be attacked, and while an enemy Guard is up
+/// every attacker but a Ranged one has to go for a Guard monster.
+pub fn check_attack(
+    attacker: &Monster,
+    slot: usize,
+    defenders: &[Option<Monster>],
+    target: TargetType,
+) -> Result<(), ActionError> {
+    if !attacker.can_attack() {
+        return Err(ActionError::CannotAttack(slot));
+    }
+    let guarded = !attacker.has_keyword(Keyword::Ranged)
+        && defenders.iter().flatten().any(|m| m.is_guarding());
+    let target_slot = match target {
+        TargetType::Hero if guarded => return Err(ActionError::GuardInTheWay),
+        TargetType::Hero => return Ok(()),
+        TargetType::Monster(target_slot) => target_slot,
+    };
+    let defender = defenders
+        .get(target_slot)
+        .and_then(|m| m.as_ref())
+        .ok_or(ActionError::NoMonster(target_slot))?;
+    if !in_range(slot, target_slot, attacker.attack_type().range()) {
+        Err(ActionError::OutOfRange)
+    } else if defender.is_stealthed() {
+        Err(ActionError::TargetStealthed)
+    } else if guarded && !defender.is_guarding() {
+        Err(ActionError::GuardInTheWay)
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks that `hero` may attack `target` on a field of `defenders` with its weapon.
+/// Heroes follow the same rules as a monster standing in `HERO_COLUMN`, but never
+/// count as Ranged.
+pub fn check_hero_attack(
+    hero: &Hero,
+    defenders: &[Option<Monster>],
+    target: TargetType,
+) -> Result<(), ActionError> {
+    let weapon = hero.weapon().ok_or(ActionError::NoWeapon)?;
+    if !hero.can_attack() {
+        return Err(ActionError::HeroCannotAttack);
+    }
+    let guarded = defenders.iter().flatten().any(|m| m.is_guarding());
+    let target_slot = match target {
+        TargetType::Hero if guarded => return Err(ActionError::GuardInTheWay),
+        TargetType::Hero => return Ok(()),
+        TargetType::Monster(target_slot) => target_slot,
+    };
+    let defender = defenders
+        .get(target_slot)
+        .and_then(|m| m.as_ref())
+        .ok_or(ActionError::NoMonster(target_slot))?;
+    if !in_range(HERO_COLUMN, target_slot, weapon.attack_type().range()) {
+        Err(ActionError::OutOfRange)
+    } else if defender.is_stealthed() {
+        Err(ActionError::TargetStealthed)
+    } else if guarded && !defender.is_guarding() {
+        Err(ActionError::GuardInTheWay)
+    } else {
+        Ok(())
+    }
+}
+
+pub fn is_legal_attack(
+    attacker: &Monster,
+    slot: usize,
+    defenders: &[Option<Monster>],
+    target: TargetType,
+) -> bool {
+    check_attack(attacker, slot, defenders, target).is_ok()
+}
+
+/// Resolves an attack by the monster in `slot` of `attacker` against `target` on `defender`.
+/// An attacked monster strikes back if the attacker stands within its own range, and a
+/// Cleave attacker hits the monsters next to its target as well, who don't strike back.
+/// Damage both ways goes through the matchup table and leaves school statuses behind.
+pub fn attack(
+    attacker: &mut Board,
+    defender: &mut Board,
+    slot: usize,
+    target: TargetType,
+    matchups: &MatchupTable,
+) -> Result<(), ActionError> {
+    let monster = attacker.monster(slot).ok_or(ActionError::NoMonster(slot))?;
+    check_attack(monster, slot, &defender.field(), target)?;
+    let (damage, school, lifesteal, cleave) = (
+        monster.damage(),
+        monster.school(),
+        monster.has_keyword(Keyword::Lifesteal),
+        monster.has_keyword(Keyword::Cleave),
+    );
+
+    let dealt = match target {
+        TargetType::Hero => {
+            let hero = defender.hero_mut();
+            let damage = matchups.damage_to_hero(damage, school, hero);
+            hero.take_damage(damage);
+            damage
+        }
+        TargetType::Monster(target_slot) => {
+            let splashed = if cleave {
+                defender.neighbours(target_slot)
+            } else {
+                vec![]
+            };
+            let mut dealt = status::hit_monster(defender, target_slot, damage, school, matchups);
+            for neighbour in splashed {
+                dealt += status::hit_monster(defender, neighbour, damage, school, matchups);
+            }
+            let retaliation = defender.monster(target_slot).map(|m| {
+                (
+                    m.damage(),
+                    m.attack_type().range(),
+                    m.school(),
+                    m.has_keyword(Keyword::Lifesteal),
+                )
+            });
+            if let Some((retaliation, reach, retaliation_school, drains)) = retaliation {
+                if in_range(target_slot, slot, reach) {
+                    let drained = status::hit_monster(
+                        attacker,
+                        slot,
+                        retaliation,
+                        retaliation_school,
+                        matchups,
+                    );
+                    if drains {
+                        defender.hero_mut().heal(drained);
+                    }
+                }
+            }
+            dealt
+        }
+    };
+    if lifesteal {
+        attacker.hero_mut().heal(dealt);
+    }
+
+    status::on_attack(attacker, slot);
+    if let Some(monster) = attacker.monster_mut(slot) {
+        monster.exhaust();
+        monster.break_stealth();
+    }
+    attacker.remove_dead_monsters();
+    defender.remove_dead_monsters();
+    Ok(())
+}
+
+/// Resolves an attack by the hero of `attacker` against `target` on `defender`. An
+/// attacked monster strikes back at the hero if `HERO_COLUMN` is within its range.
+/// Every attack wears the weapon down, and a broken one goes to the graveyard.
+pub fn hero_attack(
+    attacker: &mut Board,
+    defender: &mut Board,
+    target: TargetType,
+    matchups: &MatchupTable,
+) -> Result<(), ActionError> {
+    check_hero_attack(attacker.hero(), &defender.field(), target)?;
+    let (damage, school) = match attacker.hero().weapon() {
+        Some(weapon) => (weapon.attack(), weapon.school()),
+        None => return Err(ActionError::NoWeapon),
+    };
+
+    match target {
+        TargetType::Hero => {
+            let hero = defender.hero_mut();
+            let damage = matchups.damage_to_hero(damage, school, hero);
+            hero.take_damage(damage);
+        }
+        TargetType::Monster(target_slot) => {
+            status::hit_monster(defender, target_slot, damage, school, matchups);
+            let retaliation = defender.monster(target_slot).map(|m| {
+                (
+                    m.damage(),
+                    m.attack_type().range(),
+                    m.school(),
+                    m.has_keyword(Keyword::Lifesteal),
+                )
+            });
+            if let Some((retaliation, reach, retaliation_school, drains)) = retaliation {
+                if in_range(target_slot, HERO_COLUMN, reach) {
+                    let hero = attacker.hero_mut();
+                    let drained = matchups.damage_to_hero(retaliation, retaliation_school, hero);
+                    hero.take_damage(drained);
+                    if drains {
+                        defender.hero_mut().heal(drained);
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(broken) = attacker.hero_mut().swing() {
+        attacker.send_to_graveyard(CardHolder::WeaponCard(broken.reset()));
+    }
+    defender.remove_dead_monsters();
+    Ok(())
+}
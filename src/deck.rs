@@ -0,0 +1,231 @@
+use super::card_data::CardDatabase;
+use super::entities::{CardHolder, HeroClass};
+use bracket_random::prelude::RandomNumberGenerator;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const DECK_DIR: &str = "data/decks";
+pub const DECK_SIZE: usize = 30;
+pub const MAX_COPIES: u32 = 3;
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct DeckEntry {
+    id: String,
+    copies: u32,
+}
+
+impl DeckEntry {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn copies(&self) -> u32 {
+        self.copies
+    }
+}
+
+/// A named deck: which hero it is played with and how many copies of each card it holds.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct DeckList {
+    name: String,
+    class: HeroClass,
+    cards: Vec<DeckEntry>,
+}
+
+impl DeckList {
+    pub fn new(name: String, class: HeroClass) -> DeckList {
+        DeckList {
+            name,
+            class,
+            cards: vec![],
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn class(&self) -> HeroClass {
+        self.class
+    }
+
+    pub fn set_class(&mut self, class: HeroClass) {
+        self.class = class;
+    }
+
+    pub fn cards(&self) -> &[DeckEntry] {
+        &self.cards
+    }
+
+    pub fn size(&self) -> usize {
+        self.cards.iter().map(|e| e.copies as usize).sum()
+    }
+
+    pub fn copies(&self, id: &str) -> u32 {
+        self.cards
+            .iter()
+            .find(|e| e.id == id)
+            .map_or(0, |e| e.copies)
+    }
+
+    /// Adds one copy of `id`. Limits are only checked by `validate`, so a deck can be
+    /// edited through states that aren't legal yet.
+    pub fn add_copy(&mut self, id: &str) {
+        match self.cards.iter_mut().find(|e| e.id == id) {
+            Some(entry) => entry.copies += 1,
+            None => self.cards.push(DeckEntry {
+                id: id.to_string(),
+                copies: 1,
+            }),
+        }
+    }
+
+    pub fn remove_copy(&mut self, id: &str) {
+        if let Some(entry) = self.cards.iter_mut().find(|e| e.id == id) {
+            entry.copies = entry.copies.saturating_sub(1);
+        }
+        self.cards.retain(|e| e.copies > 0);
+    }
+
+    /// Checks the deck against the card pool and the deck building rules. Class cards
+    /// only go in decks of their class.
+    pub fn validate(&self, db: &CardDatabase) -> Result<(), DeckError> {
+        for entry in self.cards.iter() {
+            let card = db
+                .card(&entry.id, 0)
+                .ok_or_else(|| DeckError::UnknownCard(entry.id.clone()))?;
+            if let Some(class) = card.class().filter(|class| *class != self.class) {
+                return Err(DeckError::WrongClass {
+                    id: entry.id.clone(),
+                    class,
+                });
+            }
+            if entry.copies > MAX_COPIES {
+                return Err(DeckError::TooManyCopies {
+                    id: entry.id.clone(),
+                    copies: entry.copies,
+                });
+            }
+        }
+        if self.size() != DECK_SIZE {
+            return Err(DeckError::WrongSize(self.size()));
+        }
+        Ok(())
+    }
+
+    /// Turns the list into a shuffled deck of cards, ready for `Board::new`.
+    pub fn build(
+        &self,
+        db: &CardDatabase,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<Vec<CardHolder>, DeckError> {
+        self.validate(db)?;
+        let mut deck = vec![];
+        for entry in self.cards.iter() {
+            for _ in 0..entry.copies {
+                let card = db
+                    .card(&entry.id, deck.len() as u32)
+                    .ok_or_else(|| DeckError::UnknownCard(entry.id.clone()))?;
+                deck.push(card);
+            }
+        }
+        for i in (1..deck.len()).rev() {
+            let j = rng.range(0, i + 1);
+            deck.swap(i, j);
+        }
+        Ok(deck)
+    }
+}
+
+#[derive(Debug)]
+pub enum DeckError {
+    Io { path: PathBuf, source: io::Error },
+    Parse { path: PathBuf, message: String },
+    UnknownCard(String),
+    TooManyCopies { id: String, copies: u32 },
+    WrongClass { id: String, class: HeroClass },
+    WrongSize(usize),
+}
+
+impl fmt::Display for DeckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeckError::Io { path, source } => {
+                write!(f, "could not access {}: {}", path.display(), source)
+            }
+            DeckError::Parse { path, message } => {
+                write!(f, "malformed deck file {}: {}", path.display(), message)
+            }
+            DeckError::UnknownCard(id) => write!(f, "no card called \"{}\"", id),
+            DeckError::TooManyCopies { id, copies } => write!(
+                f,
+                "{} copies of \"{}\", at most {} allowed",
+                copies, id, MAX_COPIES
+            ),
+            DeckError::WrongClass { id, class } => {
+                write!(f, "\"{}\" can only go in a {} deck", id, class.name())
+            }
+            DeckError::WrongSize(size) => {
+                write!(f, "deck has {} cards, needs {}", size, DECK_SIZE)
+            }
+        }
+    }
+}
+
+impl Error for DeckError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DeckError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+pub fn load_deck(path: &Path) -> Result<DeckList, DeckError> {
+    let contents = fs::read_to_string(path).map_err(|source| DeckError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    ron::de::from_str(&contents).map_err(|e| DeckError::Parse {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    })
+}
+
+pub fn save_deck(deck: &DeckList, path: &Path) -> Result<(), DeckError> {
+    let contents = ron::ser::to_string_pretty(deck, ron::ser::PrettyConfig::new()).map_err(
+        |e| DeckError::Parse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        },
+    )?;
+    fs::write(path, contents).map_err(|source| DeckError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Every `.ron` deck in `dir`, in file name order, together with the file it came from.
+pub fn load_decks(dir: &Path) -> Result<Vec<(PathBuf, DeckList)>, DeckError> {
+    let io_error = |source| DeckError::Io {
+        path: dir.to_path_buf(),
+        source,
+    };
+    let mut paths = vec![];
+    for entry in fs::read_dir(dir).map_err(io_error)? {
+        let path = entry.map_err(io_error)?.path();
+        if path.extension().and_then(|e| e.to_str()) == Some("ron") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| load_deck(&path).map(|deck| (path, deck)))
+        .collect()
+}
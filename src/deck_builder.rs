@@ -0,0 +1,273 @@
+use rltk::{Rltk, VirtualKeyCode, RGB};
+use specs::prelude::*;
+use std::cmp::min;
+use std::path::{Path, PathBuf};
+use tcg_project::card_data::CardDatabase;
+use tcg_project::deck::{self, DeckList, DECK_DIR, DECK_SIZE, MAX_COPIES};
+use tcg_project::entities::{Card, CardHolder, HeroClass};
+
+const POOL_TOP: i32 = 3;
+const DETAIL_X: i32 = 28;
+const DECK_X: i32 = 48;
+
+/// The decks on the deck builder screen, which one is being edited and which card of
+/// the pool is highlighted.
+pub struct DeckBuilder {
+    decks: Vec<(PathBuf, DeckList)>,
+    current: usize,
+    cursor: usize,
+    message: String,
+}
+
+impl DeckBuilder {
+    pub fn new(decks: Vec<(PathBuf, DeckList)>) -> DeckBuilder {
+        let mut builder = DeckBuilder {
+            decks,
+            current: 0,
+            cursor: 0,
+            message: String::new(),
+        };
+        if builder.decks.is_empty() {
+            builder.new_deck();
+        }
+        builder
+    }
+
+    pub fn decks(&self) -> &[(PathBuf, DeckList)] {
+        &self.decks
+    }
+
+    pub fn current(&self) -> &DeckList {
+        &self.decks[self.current].1
+    }
+
+    fn current_mut(&mut self) -> &mut DeckList {
+        &mut self.decks[self.current].1
+    }
+
+    pub fn set_message(&mut self, message: String) {
+        self.message = message;
+    }
+
+    /// Starts an empty deck in a file that doesn't exist yet.
+    fn new_deck(&mut self) {
+        let mut n = self.decks.len() + 1;
+        let path = loop {
+            let path = Path::new(DECK_DIR).join(format!("deck_{}.ron", n));
+            if !path.exists() && self.decks.iter().all(|(p, _)| *p != path) {
+                break path;
+            }
+            n += 1;
+        };
+        let deck = DeckList::new(format!("Deck {}", n), HeroClass::Mage);
+        self.decks.push((path, deck));
+        self.current = self.decks.len() - 1;
+    }
+
+    fn save(&mut self) {
+        let (path, deck) = &self.decks[self.current];
+        self.message = match deck::save_deck(deck, path) {
+            Ok(()) => format!("Saved to {}", path.display()),
+            Err(e) => e.to_string(),
+        };
+    }
+}
+
+/// The lines describing a card of the pool next to the list.
+fn card_details(card: &CardHolder) -> Vec<String> {
+    let mut lines = match card {
+        CardHolder::MonsterCard(monster) => {
+            let mut lines = vec![
+                format!("COST {}", monster.cost()),
+                format!("HEALTH {}", monster.health()),
+                format!("DAMAGE {}", monster.damage()),
+                monster.attack_type().name(),
+            ];
+            lines.extend(monster.abilities().iter().map(|a| a.describe()));
+            if let Some(aura) = monster.aura() {
+                lines.push(format!("AURA {:+}/{:+}", aura.damage(), aura.health()));
+            }
+            lines
+        }
+        CardHolder::MagicCard(magic) => {
+            let mut lines = vec![format!("COST {}", magic.cost())];
+            lines.extend(magic.effects().iter().map(|e| e.describe()));
+            lines.extend(magic.abilities().iter().map(|a| a.describe()));
+            if magic.lingers() > 0 {
+                lines.push(format!("LINGERS {}", magic.lingers()));
+            }
+            lines
+        }
+        CardHolder::WeaponCard(weapon) => vec![
+            format!("COST {}", weapon.cost()),
+            format!("ATTACK {}", weapon.attack()),
+            format!("DURABILITY {}", weapon.durability()),
+            weapon.attack_type().name(),
+        ],
+    };
+    if let Some(class) = card.class() {
+        lines.push(format!("{} ONLY", class.name()));
+    }
+    lines
+}
+
+/// What the hero of `class` can do, e.g. "MAGE POWER: Fireblast (2) DMG 1".
+fn hero_power_line(db: &CardDatabase, class: HeroClass) -> String {
+    match db.hero_power(class) {
+        Some(power) => {
+            let effects: Vec<String> = power.effects().iter().map(|e| e.describe()).collect();
+            format!(
+                "{} POWER: {} ({}) {}",
+                class.name(),
+                power.data().name(),
+                power.cost(),
+                effects.join(", ")
+            )
+        }
+        None => format!("{} HAS NO POWER", class.name()),
+    }
+}
+
+pub fn draw_deck_builder(ecs: &World, ctx: &mut Rltk) {
+    let builder = ecs.fetch::<DeckBuilder>();
+    let db = ecs.fetch::<CardDatabase>();
+    let deck = builder.current();
+    let white = RGB::named(rltk::WHITE);
+    let black = RGB::named(rltk::BLACK);
+
+    ctx.print_color(
+        0,
+        0,
+        RGB::named(rltk::YELLOW),
+        black,
+        format!(
+            "DECK BUILDER - {} ({}/{})  {} - {}/{} CARDS",
+            deck.name(),
+            builder.current + 1,
+            builder.decks.len(),
+            deck.class().name(),
+            deck.size(),
+            DECK_SIZE
+        ),
+    );
+    ctx.print_color(0, 1, white, black, "CARD POOL");
+    ctx.print_color(DECK_X, 1, white, black, "IN DECK");
+    ctx.print_color(
+        0,
+        2,
+        RGB::named(rltk::CYAN),
+        black,
+        hero_power_line(&db, deck.class()),
+    );
+
+    for (row, id) in db.card_ids().into_iter().enumerate() {
+        let card = match db.card(id, 0) {
+            Some(card) => card,
+            None => continue,
+        };
+        let copies = deck.copies(id);
+        let marker = if row == builder.cursor { ">" } else { " " };
+        let other_class = card.class().is_some_and(|class| class != deck.class());
+        let fg = if copies > MAX_COPIES || (copies > 0 && other_class) {
+            RGB::named(rltk::RED)
+        } else if other_class {
+            RGB::named(rltk::DARK_GREY)
+        } else if copies > 0 {
+            RGB::named(rltk::GREEN)
+        } else {
+            white
+        };
+        ctx.print_color(
+            0,
+            POOL_TOP + row as i32,
+            fg,
+            black,
+            format!("{} {:<20} x{}", marker, card.name(), copies),
+        );
+        if row == builder.cursor {
+            for (offset, line) in card_details(&card).into_iter().enumerate() {
+                ctx.print_color(
+                    DETAIL_X,
+                    POOL_TOP + offset as i32,
+                    RGB::named(rltk::MAGENTA),
+                    black,
+                    line,
+                );
+            }
+        }
+    }
+
+    for (row, entry) in deck.cards().iter().enumerate() {
+        let name = db
+            .card(entry.id(), 0)
+            .map_or_else(|| entry.id().to_string(), |c| c.name());
+        ctx.print_color(
+            DECK_X,
+            POOL_TOP + row as i32,
+            white,
+            black,
+            format!("{}x {}", entry.copies(), name),
+        );
+    }
+
+    let help_y = POOL_TOP + 2 + db.card_ids().len().max(deck.cards().len()) as i32;
+    ctx.print_color(
+        0,
+        help_y,
+        white,
+        black,
+        "[UP/DOWN] BROWSE  [RIGHT/LEFT] ADD/REMOVE  [TAB] NEXT DECK  [N] NEW DECK",
+    );
+    ctx.print_color(
+        0,
+        help_y + 1,
+        white,
+        black,
+        "[C] CHANGE HERO  [S] SAVE DECK  [ENTER] PLAY WITH THIS DECK",
+    );
+    ctx.print_color(
+        0,
+        help_y + 3,
+        RGB::named(rltk::YELLOW),
+        black,
+        &builder.message,
+    );
+}
+
+/// Handles a key press on the deck builder screen. Returns the deck to start a match
+/// with once the player picks one that passes validation.
+pub fn deck_builder_input(ecs: &mut World, ctx: &mut Rltk) -> Option<DeckList> {
+    let key = ctx.key?;
+    let db = ecs.fetch::<CardDatabase>();
+    let mut builder = ecs.write_resource::<DeckBuilder>();
+    let ids = db.card_ids();
+    let highlighted = ids.get(builder.cursor).copied();
+
+    match key {
+        VirtualKeyCode::Up => builder.cursor = builder.cursor.saturating_sub(1),
+        VirtualKeyCode::Down => builder.cursor = min(builder.cursor + 1, ids.len().max(1) - 1),
+        VirtualKeyCode::Right => {
+            if let Some(id) = highlighted {
+                builder.current_mut().add_copy(id);
+            }
+        }
+        VirtualKeyCode::Left => {
+            if let Some(id) = highlighted {
+                builder.current_mut().remove_copy(id);
+            }
+        }
+        VirtualKeyCode::Tab => builder.current = (builder.current + 1) % builder.decks.len(),
+        VirtualKeyCode::N => builder.new_deck(),
+        VirtualKeyCode::C => {
+            let class = builder.current().class().next();
+            builder.current_mut().set_class(class);
+        }
+        VirtualKeyCode::S => builder.save(),
+        VirtualKeyCode::Return => match builder.current().validate(&db) {
+            Ok(()) => return Some(builder.current().clone()),
+            Err(e) => builder.message = e.to_string(),
+        },
+        _ => {}
+    }
+    None
+}
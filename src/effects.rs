@@ -0,0 +1,256 @@
+use super::card_data::CardDatabase;
+use super::entities::{
+    Board, Effect, EffectKind, Magic, MagicSchool, Side, Stat, StatusKind, Target, TargetSelector,
+    TargetType,
+};
+use super::status;
+use bracket_random::prelude::RandomNumberGenerator;
+use serde::{Deserialize, Serialize};
+
+/// Where an effect comes from: the school of any damage it deals, the target its
+/// player picked and, for abilities, the slot of the monster that has it.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Origin {
+    pub school: MagicSchool,
+    pub chosen: Option<Target>,
+    pub source: Option<usize>,
+}
+
+impl Origin {
+    /// A card played by its owner, aimed at `chosen`.
+    pub fn card(school: MagicSchool, chosen: Option<Target>) -> Origin {
+        Origin {
+            school,
+            chosen,
+            source: None,
+        }
+    }
+
+    /// An ability of the monster in `slot` of the caster's field.
+    pub fn ability(school: MagicSchool, slot: usize) -> Origin {
+        Origin {
+            school,
+            chosen: None,
+            source: Some(slot),
+        }
+    }
+}
+
+fn occupied_slots(board: &Board) -> Vec<usize> {
+    (0..board.field().len())
+        .filter(|slot| board.monster(*slot).is_some())
+        .collect()
+}
+
+/// Expands a selector into the concrete targets it covers right now.
+fn resolve_targets(
+    selector: TargetSelector,
+    origin: Origin,
+    caster: &Board,
+    opponent: &Board,
+    rng: &mut RandomNumberGenerator,
+) -> Vec<Target> {
+    match selector {
+        TargetSelector::Chosen => origin.chosen.into_iter().collect(),
+        TargetSelector::This => origin
+            .source
+            .map(|slot| Target::Friendly(TargetType::Monster(slot)))
+            .into_iter()
+            .collect(),
+        TargetSelector::Hero(Side::Friendly) => vec![Target::Friendly(TargetType::Hero)],
+        TargetSelector::Hero(Side::Enemy) => vec![Target::Enemy(TargetType::Hero)],
+        TargetSelector::AllMonsters(Side::Friendly) => occupied_slots(caster)
+            .into_iter()
+            .map(|slot| Target::Friendly(TargetType::Monster(slot)))
+            .collect(),
+        TargetSelector::AllMonsters(Side::Enemy) => occupied_slots(opponent)
+            .into_iter()
+            .map(|slot| Target::Enemy(TargetType::Monster(slot)))
+            .collect(),
+        TargetSelector::RandomMonster(Side::Friendly) => rng
+            .random_slice_entry(&occupied_slots(caster))
+            .map(|slot| Target::Friendly(TargetType::Monster(*slot)))
+            .into_iter()
+            .collect(),
+        TargetSelector::RandomMonster(Side::Enemy) => rng
+            .random_slice_entry(&occupied_slots(opponent))
+            .map(|slot| Target::Enemy(TargetType::Monster(*slot)))
+            .into_iter()
+            .collect(),
+        // Cards on the stack aren't on either board, see `stack::pass`.
+        TargetSelector::Responded => vec![],
+    }
+}
+
+fn apply(
+    kind: &EffectKind,
+    school: MagicSchool,
+    target: Target,
+    caster: &mut Board,
+    opponent: &mut Board,
+    db: &CardDatabase,
+    rng: &mut RandomNumberGenerator,
+) {
+    if let EffectKind::DamagePerGraveyardCard(per_card) = kind {
+        let amount = per_card * caster.graveyard().len() as i32;
+        let damage = EffectKind::DealDamage(amount);
+        return apply(&damage, school, target, caster, opponent, db, rng);
+    }
+    let (board, target_type) = match target {
+        Target::Friendly(t) => (caster, t),
+        Target::Enemy(t) => (opponent, t),
+    };
+
+    match (kind, target_type) {
+        (EffectKind::DealDamage(amount), TargetType::Hero) => {
+            let hero = board.hero_mut();
+            let damage = db.matchups().damage_to_hero(*amount, school, hero);
+            hero.take_damage(damage);
+        }
+        (EffectKind::DealDamage(amount), TargetType::Monster(slot)) => {
+            status::hit_monster(board, slot, *amount, school, db.matchups());
+        }
+        (EffectKind::Heal(amount), TargetType::Hero) => board.hero_mut().heal(*amount),
+        (EffectKind::Heal(amount), TargetType::Monster(slot)) => {
+            if let Some(monster) = board.monster_mut(slot) {
+                monster.heal(*amount);
+                if school == MagicSchool::Light {
+                    monster.add_status(StatusKind::Blessing);
+                }
+            }
+        }
+        (EffectKind::Draw(count), _) => {
+            for _ in 0..*count {
+                board.draw_card();
+            }
+        }
+        (EffectKind::Discard(count), _) => board.require_discards(*count),
+        (EffectKind::DiscardRandom(count), _) => board.discard_random(*count, rng),
+        (EffectKind::Buff { health, damage }, TargetType::Monster(slot)) => {
+            if let Some(monster) = board.monster_mut(slot) {
+                monster.buff(*health, *damage);
+            }
+        }
+        (EffectKind::BuffThisTurn { health, damage }, TargetType::Monster(slot)) => {
+            if let Some(monster) = board.monster_mut(slot) {
+                monster.buff_until_end_of_turn(*health, *damage);
+            }
+        }
+        (EffectKind::SetHealth(health), TargetType::Monster(slot)) => {
+            if let Some(monster) = board.monster_mut(slot) {
+                monster.set_stat(Stat::Health, *health);
+            }
+        }
+        (EffectKind::SetDamage(damage), TargetType::Monster(slot)) => {
+            if let Some(monster) = board.monster_mut(slot) {
+                monster.set_stat(Stat::Damage, *damage);
+            }
+        }
+        (EffectKind::Silence, TargetType::Monster(slot)) => {
+            if let Some(monster) = board.monster_mut(slot) {
+                monster.silence();
+            }
+            board.refresh_auras();
+        }
+        (EffectKind::Splash(amount), TargetType::Monster(slot)) => {
+            let neighbours = board.neighbours(slot);
+            status::hit_monster(board, slot, *amount, school, db.matchups());
+            for neighbour in neighbours {
+                status::hit_monster(board, neighbour, *amount, school, db.matchups());
+            }
+        }
+        (EffectKind::Summon(id), target_type) => {
+            let slot = match target_type {
+                TargetType::Monster(slot) => Some(slot),
+                TargetType::Hero => board.first_free_slot(),
+            };
+            if let (Some(slot), Some(data)) = (slot, db.monster(id)) {
+                board.summon(slot, data.clone());
+            }
+        }
+        (EffectKind::Destroy, TargetType::Monster(slot)) => {
+            if let Some(monster) = board.monster_mut(slot) {
+                monster.destroy();
+            }
+        }
+        (EffectKind::ReturnToHand, TargetType::Monster(slot)) => {
+            board.return_to_hand(slot);
+        }
+        (EffectKind::ReturnToHand, TargetType::Hero) => {
+            board.recover_from_graveyard();
+        }
+        (EffectKind::Resurrect, target_type) => {
+            let slot = match target_type {
+                TargetType::Monster(slot) => Some(slot),
+                TargetType::Hero => board.first_free_slot(),
+            };
+            if let Some(slot) = slot {
+                board.resurrect(slot);
+            }
+        }
+        (EffectKind::Exile, TargetType::Monster(slot)) => {
+            board.exile_monster(slot);
+        }
+        (EffectKind::Exile, TargetType::Hero) => {
+            board.exile_graveyard();
+        }
+        (EffectKind::GainArmor(amount), TargetType::Hero) => board.hero_mut().gain_armor(*amount),
+        // Graveyard damage was turned into plain damage above.
+        (EffectKind::DamagePerGraveyardCard(_), _) => {}
+        (EffectKind::Buff { .. }, TargetType::Hero)
+        | (EffectKind::BuffThisTurn { .. }, TargetType::Hero)
+        | (EffectKind::SetHealth(_), TargetType::Hero)
+        | (EffectKind::SetDamage(_), TargetType::Hero)
+        | (EffectKind::Silence, TargetType::Hero)
+        | (EffectKind::Splash(_), TargetType::Hero)
+        | (EffectKind::Destroy, TargetType::Hero)
+        | (EffectKind::GainArmor(_), TargetType::Monster(_))
+        | (EffectKind::Counter, _) => {}
+    }
+}
+
+/// Applies one effect to every target it covers, then clears out dead monsters.
+pub fn resolve(
+    effect: &Effect,
+    origin: Origin,
+    caster: &mut Board,
+    opponent: &mut Board,
+    db: &CardDatabase,
+    rng: &mut RandomNumberGenerator,
+) {
+    for target in resolve_targets(effect.target(), origin, caster, opponent, rng) {
+        apply(
+            effect.kind(),
+            origin.school,
+            target,
+            caster,
+            opponent,
+            db,
+            rng,
+        );
+    }
+    caster.remove_dead_monsters();
+    opponent.remove_dead_monsters();
+}
+
+/// Resolves the effects of `magic` in order. Paying for it and moving the card
+/// around is up to the caller.
+pub fn resolve_magic(
+    magic: &Magic,
+    target: Option<Target>,
+    caster: &mut Board,
+    opponent: &mut Board,
+    db: &CardDatabase,
+    rng: &mut RandomNumberGenerator,
+) {
+    for effect in magic.effects() {
+        resolve(
+            effect,
+            Origin::card(magic.school(), target),
+            caster,
+            opponent,
+            db,
+            rng,
+        );
+    }
+}
@@ -1,8 +1,14 @@
+use super::actions::ActionError;
+use super::match_log::MatchLog;
+use super::mulligan::Mulligan;
+use super::stack::EffectStack;
+use bracket_random::prelude::RandomNumberGenerator;
+use serde::{Deserialize, Serialize};
 use specs::prelude::*;
 use specs_derive::*;
-use std::any;
+use std::cmp::{max, min};
 
-#[derive(Component)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Component, Serialize, Deserialize)]
 pub enum HeroClass {
     Mage,
     Warrior,
@@ -11,10 +17,42 @@ pub enum HeroClass {
     Priest,
 }
 
-#[derive(Component)]
+impl HeroClass {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HeroClass::Mage => "MAGE",
+            HeroClass::Warrior => "WARRIOR",
+            HeroClass::Ranger => "RANGER",
+            HeroClass::Rogue => "ROGUE",
+            HeroClass::Priest => "PRIEST",
+        }
+    }
+
+    /// The class after this one, wrapping around, for cycling through them in menus.
+    pub fn next(&self) -> HeroClass {
+        match self {
+            HeroClass::Mage => HeroClass::Warrior,
+            HeroClass::Warrior => HeroClass::Ranger,
+            HeroClass::Ranger => HeroClass::Rogue,
+            HeroClass::Rogue => HeroClass::Priest,
+            HeroClass::Priest => HeroClass::Mage,
+        }
+    }
+}
+
+pub const HERO_CLASSES: [HeroClass; 5] = [
+    HeroClass::Mage,
+    HeroClass::Warrior,
+    HeroClass::Ranger,
+    HeroClass::Rogue,
+    HeroClass::Priest,
+];
+
+#[derive(Clone, Component, Serialize, Deserialize)]
 pub enum CardHolder {
     MonsterCard(Monster),
     MagicCard(Magic),
+    WeaponCard(Weapon),
 }
 
 impl CardHolder {
@@ -22,9 +60,7 @@ impl CardHolder {
         match self {
             CardHolder::MonsterCard(c) => c.data().name(),
             CardHolder::MagicCard(c) => c.data().name(),
-            _ => {
-                panic!("Invalid card type!");
-            }
+            CardHolder::WeaponCard(c) => c.data().name(),
         }
     }
 
@@ -32,18 +68,40 @@ impl CardHolder {
         match self {
             CardHolder::MonsterCard(c) => c.hidden(),
             CardHolder::MagicCard(c) => c.hidden(),
-            _ => {
-                panic!("Invalid card type!");
-            }
+            CardHolder::WeaponCard(c) => c.hidden(),
         }
     }
-    pub fn reveal(&mut self) -> () {
+
+    pub fn reveal(&mut self) {
         match self {
             CardHolder::MonsterCard(c) => c.reveal(),
             CardHolder::MagicCard(c) => c.reveal(),
-            _ => {
-                panic!("Invalid card type!");
-            }
+            CardHolder::WeaponCard(c) => c.reveal(),
+        }
+    }
+
+    pub fn cost(&self) -> i32 {
+        match self {
+            CardHolder::MonsterCard(c) => c.cost(),
+            CardHolder::MagicCard(c) => c.cost(),
+            CardHolder::WeaponCard(c) => c.cost(),
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        match self {
+            CardHolder::MonsterCard(c) => c.id(),
+            CardHolder::MagicCard(c) => c.id(),
+            CardHolder::WeaponCard(c) => c.id(),
+        }
+    }
+
+    /// The only class whose decks may hold this card, None for neutral cards.
+    pub fn class(&self) -> Option<HeroClass> {
+        match self {
+            CardHolder::MonsterCard(c) => c.data().class(),
+            CardHolder::MagicCard(c) => c.data().class(),
+            CardHolder::WeaponCard(c) => c.data().class(),
         }
     }
 }
@@ -77,17 +135,86 @@ impl CardHolder {
 //     fn id(&self) -> u32;
 // }
 
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum Target {
     Friendly(TargetType),
     Enemy(TargetType),
 }
 
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum TargetType {
     Hero,
     Monster(usize),
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Side {
+    Friendly,
+    Enemy,
+}
+
+/// Which targets an effect applies to, seen from the player who played the card.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum TargetSelector {
+    /// The target picked by the player when the card was played.
+    Chosen,
+    Hero(Side),
+    AllMonsters(Side),
+    /// The slot of the monster whose ability this is.
+    This,
+    /// The card below this one on the stack, the one it was played in response to.
+    Responded,
+    /// One monster of that side, picked at random as the effect resolves. Stealth
+    /// doesn't hide a monster from it.
+    RandomMonster(Side),
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum EffectKind {
+    DealDamage(i32),
+    Heal(i32),
+    Draw(u32),
+    Buff {
+        health: i32,
+        damage: i32,
+    },
+    /// Puts a new copy of the monster with this card identifier on the field.
+    Summon(String),
+    Destroy,
+    /// Stops the targeted card on the stack from resolving.
+    Counter,
+    /// Sends a monster back to its owner's hand. Aimed at a hero, returns the latest
+    /// card of that player's graveyard to their hand instead.
+    ReturnToHand,
+    /// Brings the latest monster of that player's graveyard back onto their field.
+    Resurrect,
+    /// Deals this much damage for every card in the caster's graveyard.
+    DamagePerGraveyardCard(i32),
+    /// Removes a monster from the game without it dying. Aimed at a hero, removes
+    /// every card in that player's graveyard instead.
+    Exile,
+    /// Gives a hero armor, which takes damage before their health does.
+    GainArmor(i32),
+    /// Sets a monster's health to this, healing any damage it had taken.
+    SetHealth(i32),
+    /// Sets a monster's attack damage to this.
+    SetDamage(i32),
+    /// Like `Buff`, but wears off at the end of the turn.
+    BuffThisTurn {
+        health: i32,
+        damage: i32,
+    },
+    /// Strips a monster of its buffs, keywords, abilities and aura.
+    Silence,
+    /// Deals this much damage to a monster and to the monsters next to it.
+    Splash(i32),
+    /// Makes a player discard this many cards of their choice before anyone acts again.
+    Discard(u32),
+    /// Makes a player discard this many cards picked at random.
+    DiscardRandom(u32),
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
 pub struct DamageType {
     range: u32,
     school: MagicSchool,
@@ -98,34 +225,26 @@ impl DamageType {
         DamageType { range, school }
     }
 
+    pub fn range(&self) -> u32 {
+        self.range
+    }
+
+    pub fn school(&self) -> MagicSchool {
+        self.school
+    }
+
     pub fn name(&self) -> String {
-        let typename: String = match self.school {
-            MagicSchool::Physical => "PHYS.".to_string(),
-            MagicSchool::Flame => "FLAME".to_string(),
-            MagicSchool::Frost => "FROST".to_string(),
-            MagicSchool::Lightning => "LIGHTN.".to_string(),
-            MagicSchool::Shadow => "SHADOW".to_string(),
-            MagicSchool::Light => "LIGHT".to_string(),
-            _ => "".to_string(),
-        };
-        format!("{}R {}", self.range, typename)
+        format!("{}R {}", self.range, self.school.name())
     }
 
     pub fn color(&self) -> (u8, u8, u8) {
-        match self.school {
-            MagicSchool::Physical => (255, 255, 255),
-            MagicSchool::Flame => (255, 140, 0),
-            MagicSchool::Frost => (0, 128, 128),
-            MagicSchool::Lightning => (0, 0, 139),
-            MagicSchool::Shadow => (128, 0, 128),
-            MagicSchool::Light => (250, 250, 210),
-            _ => (0, 0, 0),
-        }
+        self.school.color()
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
 pub enum MagicSchool {
+    #[default]
     Physical,
     Flame,
     Frost,
@@ -134,6 +253,255 @@ pub enum MagicSchool {
     Light,
 }
 
+pub const MAGIC_SCHOOLS: [MagicSchool; 6] = [
+    MagicSchool::Physical,
+    MagicSchool::Flame,
+    MagicSchool::Frost,
+    MagicSchool::Lightning,
+    MagicSchool::Shadow,
+    MagicSchool::Light,
+];
+
+impl MagicSchool {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MagicSchool::Physical => "PHYS.",
+            MagicSchool::Flame => "FLAME",
+            MagicSchool::Frost => "FROST",
+            MagicSchool::Lightning => "LIGHTN.",
+            MagicSchool::Shadow => "SHADOW",
+            MagicSchool::Light => "LIGHT",
+        }
+    }
+
+    pub fn color(&self) -> (u8, u8, u8) {
+        match self {
+            MagicSchool::Physical => (255, 255, 255),
+            MagicSchool::Flame => (255, 140, 0),
+            MagicSchool::Frost => (0, 128, 128),
+            MagicSchool::Lightning => (0, 0, 139),
+            MagicSchool::Shadow => (128, 0, 128),
+            MagicSchool::Light => (250, 250, 210),
+        }
+    }
+}
+
+/// How hard a school hits something, before the matchup table turns it into a number.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Affinity {
+    Normal,
+    Resistant,
+    Weak,
+    Immune,
+}
+
+impl Affinity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Affinity::Normal => "NORMAL",
+            Affinity::Resistant => "RESIST",
+            Affinity::Weak => "WEAK",
+            Affinity::Immune => "IMMUNE",
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Keyword {
+    /// Enemies have to attack a Guard monster before anything else.
+    Guard,
+    /// Can attack on the turn it enters the field.
+    Haste,
+    /// Damage it deals heals its owner's hero.
+    Lifesteal,
+    /// Can't be attacked or targeted by the enemy until it attacks.
+    Stealth,
+    /// Attacks past enemy Guard monsters.
+    Ranged,
+    /// Attacks also hit the monsters next to its target.
+    Cleave,
+}
+
+impl Keyword {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Keyword::Guard => "GUARD",
+            Keyword::Haste => "HASTE",
+            Keyword::Lifesteal => "LIFESTEAL",
+            Keyword::Stealth => "STEALTH",
+            Keyword::Ranged => "RANGED",
+            Keyword::Cleave => "CLEAVE",
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum StatusKind {
+    /// Takes one damage per stack at the start of its owner's turn.
+    Burn,
+    /// Can't attack.
+    Freeze,
+    /// Has already passed a Lightning hit on to a neighbour and won't do it again.
+    Shock,
+    /// Deals one less damage per stack.
+    Curse,
+    /// Heals one health per stack at the start of its owner's turn.
+    Blessing,
+}
+
+impl StatusKind {
+    /// How many of its owner's turns a fresh or refreshed status lasts.
+    pub fn duration(&self) -> u32 {
+        match self {
+            StatusKind::Burn | StatusKind::Curse | StatusKind::Blessing => 2,
+            StatusKind::Freeze | StatusKind::Shock => 1,
+        }
+    }
+
+    /// Reapplying a status adds a stack up to this many, and always refreshes its duration.
+    pub fn max_stacks(&self) -> i32 {
+        match self {
+            StatusKind::Burn | StatusKind::Curse => 3,
+            StatusKind::Freeze | StatusKind::Shock | StatusKind::Blessing => 1,
+        }
+    }
+
+    /// Short tag for the monster's card.
+    pub fn tag(&self) -> &'static str {
+        match self {
+            StatusKind::Burn => "BRN",
+            StatusKind::Freeze => "FRZ",
+            StatusKind::Shock => "SHK",
+            StatusKind::Curse => "CRS",
+            StatusKind::Blessing => "BLS",
+        }
+    }
+
+    pub fn school(&self) -> MagicSchool {
+        match self {
+            StatusKind::Burn => MagicSchool::Flame,
+            StatusKind::Freeze => MagicSchool::Frost,
+            StatusKind::Shock => MagicSchool::Lightning,
+            StatusKind::Curse => MagicSchool::Shadow,
+            StatusKind::Blessing => MagicSchool::Light,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Status {
+    kind: StatusKind,
+    stacks: i32,
+    turns: u32,
+}
+
+impl Status {
+    pub fn kind(&self) -> StatusKind {
+        self.kind
+    }
+
+    pub fn stacks(&self) -> i32 {
+        self.stacks
+    }
+
+    pub fn turns(&self) -> u32 {
+        self.turns
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Stat {
+    Cost,
+    Health,
+    Damage,
+}
+
+/// How a modifier changes its stat.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum ModifierOp {
+    /// Replaces the printed value. The latest one wins.
+    Set(i32),
+    /// Added on top once every `Set` has been applied.
+    Add(i32),
+}
+
+/// How long a modifier stays on the monster.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum ModifierDuration {
+    /// Until the monster leaves the field or is silenced.
+    Permanent,
+    /// Until the end of the current turn.
+    EndOfTurn,
+    /// For as long as the friendly monster with this card id keeps its aura in play.
+    Aura(u32),
+}
+
+/// A change to one of a monster's stats on top of what is printed on its card.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Modifier {
+    stat: Stat,
+    op: ModifierOp,
+    duration: ModifierDuration,
+}
+
+impl Modifier {
+    pub fn new(stat: Stat, op: ModifierOp, duration: ModifierDuration) -> Modifier {
+        Modifier { stat, op, duration }
+    }
+
+    pub fn stat(&self) -> Stat {
+        self.stat
+    }
+
+    pub fn op(&self) -> ModifierOp {
+        self.op
+    }
+
+    pub fn duration(&self) -> ModifierDuration {
+        self.duration
+    }
+}
+
+/// Stats a monster lends the friendly monsters in the slots next to its own.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct Aura {
+    #[serde(default)]
+    health: i32,
+    #[serde(default)]
+    damage: i32,
+}
+
+impl Aura {
+    pub fn health(&self) -> i32 {
+        self.health
+    }
+
+    pub fn damage(&self) -> i32 {
+        self.damage
+    }
+}
+
+/// The resistances, weaknesses and immunities printed on a monster or hero.
+/// Schools that aren't listed fall back to the matchup table.
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct DefenseProfile {
+    affinities: Vec<(MagicSchool, Affinity)>,
+}
+
+impl DefenseProfile {
+    pub fn new(affinities: Vec<(MagicSchool, Affinity)>) -> DefenseProfile {
+        DefenseProfile { affinities }
+    }
+
+    pub fn affinity(&self, school: MagicSchool) -> Option<Affinity> {
+        self.affinities
+            .iter()
+            .find(|(s, _)| *s == school)
+            .map(|(_, affinity)| *affinity)
+    }
+}
+
 pub trait Card {
     fn play_card_on_field(&self, board: &mut Board, target: usize) {}
     fn play_card_on_hero(&self, board: &mut Board) {}
@@ -147,6 +515,9 @@ pub trait CardData {
     fn name(&self) -> String {
         String::new()
     }
+    fn class(&self) -> Option<HeroClass> {
+        None
+    }
     fn as_monster(&self) -> Option<&MonsterData> {
         None
     }
@@ -155,24 +526,206 @@ pub trait CardData {
     }
 }
 
-#[derive(Component)]
-pub struct Effect {
-    source: CardHolder,
-    targets: Vec<CardHolder>,
-    effect: fn(usize),
+/// When a monster's ability goes off.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Trigger {
+    /// When the card is played from hand.
+    Played,
+    /// When the monster dies. It is already gone from the field, so `This` is its empty slot.
+    Died,
+    /// When its owner's turn reaches this phase: PrePlay is the start of the turn,
+    /// PostPlay the end.
+    Phase(TurnPhase),
+    /// Whenever the monster takes damage, even if the damage kills it.
+    Damaged,
+}
+
+/// An effect a monster, or a magic card lingering in play, runs by itself when its
+/// trigger happens. A magic card's own effects are its when-played abilities.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Ability {
+    trigger: Trigger,
+    effect: Effect,
+}
+
+impl Ability {
+    pub fn trigger(&self) -> Trigger {
+        self.trigger
+    }
+
+    pub fn effect(&self) -> &Effect {
+        &self.effect
+    }
+
+    /// Short text for the card, e.g. "DIES: SUMMON wisp SELF".
+    pub fn describe(&self) -> String {
+        let when = match self.trigger {
+            Trigger::Played => "PLAY",
+            Trigger::Died => "DIES",
+            Trigger::Phase(TurnPhase::PrePlay) => "TURN START",
+            Trigger::Phase(TurnPhase::PostPlay) => "TURN END",
+            Trigger::Phase(_) => "TURN",
+            Trigger::Damaged => "HIT",
+        };
+        format!("{}: {}", when, self.effect.describe())
+    }
 }
 
+/// Something that happened to a board's monsters which their abilities may react to.
+/// Queued on the board and drained by `triggers::resolve_pending`.
 #[derive(Clone)]
+pub enum BoardEvent {
+    Played(usize),
+    /// Keeps the monster as it was hit, so it still reacts if the damage killed it.
+    Damaged(usize, Monster),
+    Died(usize, Monster),
+}
+
+/// Something that happened to a player's cards that both players get told about.
+/// Queued on the board and moved into the `MatchLog` by `match_log::collect`.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum LogEvent {
+    /// Drawn into a full hand and put straight into the graveyard.
+    Burned(CardHolder),
+    Discarded(CardHolder),
+}
+
+impl LogEvent {
+    /// Short text for the log panel, e.g. "BURNED Fireball".
+    pub fn describe(&self) -> String {
+        match self {
+            LogEvent::Burned(card) => format!("BURNED {}", card.name()),
+            LogEvent::Discarded(card) => format!("DISCARDED {}", card.name()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Effect {
+    kind: EffectKind,
+    target: TargetSelector,
+}
+
+impl Effect {
+    pub fn kind(&self) -> &EffectKind {
+        &self.kind
+    }
+
+    pub fn target(&self) -> TargetSelector {
+        self.target
+    }
+
+    /// Whether the player may pick `target` for this effect. Stealthed enemies can't be
+    /// picked, and neither can a hero for effects that only work on monsters.
+    pub fn can_target(&self, target: Target, caster: &Board, opponent: &Board) -> bool {
+        let (board, target_type) = match target {
+            Target::Friendly(t) => (caster, t),
+            Target::Enemy(t) => (opponent, t),
+        };
+        match (&self.kind, target_type) {
+            (EffectKind::Summon(_), TargetType::Monster(slot)) => {
+                matches!(board.field.get(slot), Some(None))
+            }
+            (EffectKind::Summon(_), TargetType::Hero) => board.first_free_slot().is_some(),
+            (EffectKind::Resurrect, TargetType::Monster(slot)) => {
+                matches!(board.field.get(slot), Some(None)) && board.last_buried_monster().is_some()
+            }
+            (EffectKind::Resurrect, TargetType::Hero) => {
+                board.first_free_slot().is_some() && board.last_buried_monster().is_some()
+            }
+            (EffectKind::Draw(_), TargetType::Hero)
+            | (EffectKind::Discard(_), TargetType::Hero)
+            | (EffectKind::DiscardRandom(_), TargetType::Hero)
+            | (EffectKind::DealDamage(_), TargetType::Hero)
+            | (EffectKind::Heal(_), TargetType::Hero)
+            | (EffectKind::DamagePerGraveyardCard(_), TargetType::Hero)
+            | (EffectKind::Exile, TargetType::Hero)
+            | (EffectKind::GainArmor(_), TargetType::Hero) => true,
+            (EffectKind::ReturnToHand, TargetType::Hero) => !board.graveyard.is_empty(),
+            (EffectKind::Counter, _)
+            | (_, TargetType::Hero)
+            | (EffectKind::Draw(_), _)
+            | (EffectKind::Discard(_), _)
+            | (EffectKind::DiscardRandom(_), _)
+            | (EffectKind::GainArmor(_), _) => false,
+            (_, TargetType::Monster(slot)) => match (target, board.monster(slot)) {
+                (Target::Enemy(_), Some(monster)) => !monster.is_stealthed(),
+                (_, monster) => monster.is_some(),
+            },
+        }
+    }
+
+    /// Short text for card panels, at most one card width long.
+    pub fn describe(&self) -> String {
+        let action = match &self.kind {
+            EffectKind::DealDamage(n) => format!("DMG {}", n),
+            EffectKind::Heal(n) => format!("HEAL {}", n),
+            EffectKind::Draw(n) => format!("DRAW {}", n),
+            EffectKind::Buff { health, damage } => format!("{:+}/{:+}", damage, health),
+            EffectKind::Summon(id) => format!("SUMMON {}", id),
+            EffectKind::Destroy => "DESTROY".to_string(),
+            EffectKind::Counter => "COUNTER".to_string(),
+            EffectKind::ReturnToHand => "RETURN".to_string(),
+            EffectKind::Resurrect => "RAISE".to_string(),
+            EffectKind::DamagePerGraveyardCard(n) => format!("DMG {}xGRAVE", n),
+            EffectKind::Exile => "EXILE".to_string(),
+            EffectKind::GainArmor(n) => format!("ARMOR {}", n),
+            EffectKind::SetHealth(n) => format!("HP={}", n),
+            EffectKind::SetDamage(n) => format!("DMG={}", n),
+            EffectKind::BuffThisTurn { health, damage } => {
+                format!("{:+}/{:+} TURN", damage, health)
+            }
+            EffectKind::Silence => "SILENCE".to_string(),
+            EffectKind::Splash(n) => format!("SPLASH {}", n),
+            EffectKind::Discard(n) => format!("DISCARD {}", n),
+            EffectKind::DiscardRandom(n) => format!("DISC {} RNG", n),
+        };
+        let target = match self.target {
+            TargetSelector::Chosen => "",
+            TargetSelector::Hero(Side::Friendly) => " OWN",
+            TargetSelector::Hero(Side::Enemy) => " FOE",
+            TargetSelector::AllMonsters(Side::Friendly) => " OWN ALL",
+            TargetSelector::AllMonsters(Side::Enemy) => " FOE ALL",
+            TargetSelector::This => " SELF",
+            TargetSelector::Responded => " SPELL",
+            TargetSelector::RandomMonster(Side::Friendly) => " OWN RANDOM",
+            TargetSelector::RandomMonster(Side::Enemy) => " FOE RANDOM",
+        };
+        format!("{}{}", action, target)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct MonsterData {
+    id: String,
     name: String,
     base_cost: i32,
     base_health: i32,
     base_damage: i32,
     attack_type: DamageType,
+    #[serde(default)]
+    defense: DefenseProfile,
+    #[serde(default)]
+    keywords: Vec<Keyword>,
+    #[serde(default)]
+    abilities: Vec<Ability>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "super::card_data::present"
+    )]
+    class: Option<HeroClass>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "super::card_data::present"
+    )]
+    aura: Option<Aura>,
 }
 
 impl MonsterData {
     pub fn new(
+        id: String,
         name: String,
         cost: i32,
         health: i32,
@@ -180,14 +733,24 @@ impl MonsterData {
         attack_type: DamageType,
     ) -> MonsterData {
         MonsterData {
+            id,
             name: name,
             base_cost: cost,
             base_health: health,
             base_damage: damage,
             attack_type: attack_type,
+            defense: DefenseProfile::default(),
+            keywords: vec![],
+            abilities: vec![],
+            class: None,
+            aura: None,
         }
     }
 
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
     pub fn base_cost(&self) -> i32 {
         self.base_cost
     }
@@ -203,6 +766,22 @@ impl MonsterData {
     pub fn attack_type(&self) -> DamageType {
         self.attack_type
     }
+
+    pub fn defense(&self) -> &DefenseProfile {
+        &self.defense
+    }
+
+    pub fn keywords(&self) -> &[Keyword] {
+        &self.keywords
+    }
+
+    pub fn abilities(&self) -> &[Ability] {
+        &self.abilities
+    }
+
+    pub fn aura(&self) -> Option<Aura> {
+        self.aura
+    }
 }
 
 impl CardData for MonsterData {
@@ -210,6 +789,10 @@ impl CardData for MonsterData {
         self.name.clone()
     }
 
+    fn class(&self) -> Option<HeroClass> {
+        self.class
+    }
+
     fn as_monster(&self) -> Option<&MonsterData> {
         Some(self)
     }
@@ -221,116 +804,683 @@ impl CardData for MonsterData {
 //     }
 // }
 
-#[derive(Clone, Component)]
+#[derive(Clone, Component, Serialize, Deserialize)]
 pub struct Monster {
     id: u32,
-    cost: i32,
-    health: i32,
-    damage: i32,
     data: MonsterData,
     hidden: bool,
+    exhausted: bool,
+    #[serde(default)]
+    statuses: Vec<Status>,
+    #[serde(default)]
+    stealthed: bool,
+    /// Everything changing the printed stats, oldest first.
+    #[serde(default)]
+    modifiers: Vec<Modifier>,
+    /// Damage taken since the monster was last fully healed. Its health is its
+    /// maximum health less this.
+    #[serde(default)]
+    damage_taken: i32,
+    #[serde(default)]
+    silenced: bool,
+    #[serde(default)]
+    moved: bool,
 }
 
 impl Monster {
     pub fn new(id: u32, data: MonsterData) -> Monster {
         Monster {
             id: id,
-            cost: data.base_cost(),
-            health: data.base_health(),
-            damage: data.base_damage(),
+            exhausted: !data.keywords().contains(&Keyword::Haste),
+            stealthed: data.keywords().contains(&Keyword::Stealth),
             data: data,
             hidden: true,
+            statuses: vec![],
+            modifiers: vec![],
+            damage_taken: 0,
+            silenced: false,
+            moved: false,
         }
     }
 
-    pub fn cost(&self) -> i32 {
-        self.cost
+    /// The same card as it was printed, without damage, buffs or statuses.
+    pub fn reset(&self) -> Monster {
+        Monster::new(self.id, self.data.clone())
     }
 
-    pub fn health(&self) -> i32 {
-        self.health
+    /// The card's abilities, none once it has been silenced.
+    pub fn abilities(&self) -> &[Ability] {
+        if self.silenced {
+            &[]
+        } else {
+            self.data.abilities()
+        }
     }
 
-    pub fn damage(&self) -> i32 {
-        self.damage
+    fn keywords(&self) -> &[Keyword] {
+        if self.silenced {
+            &[]
+        } else {
+            self.data.keywords()
+        }
     }
-}
 
-impl Card for Monster {
-    fn play_card_on_field(&self, board: &mut Board, target: usize) {
-        board.field[target] = Some(self.clone());
+    pub fn has_keyword(&self, keyword: Keyword) -> bool {
+        self.keywords().contains(&keyword)
     }
 
-    fn data(&self) -> Box<&dyn CardData> {
-        Box::new(&self.data)
+    /// The aura the monster lends its neighbours, none once it has been silenced.
+    pub fn aura(&self) -> Option<Aura> {
+        self.data.aura().filter(|_| !self.silenced)
     }
 
-    fn play_card_on_hero(&self, board: &mut Board) {}
+    pub fn is_silenced(&self) -> bool {
+        self.silenced
+    }
 
-    fn id(&self) -> u32 {
-        self.id
+    /// Keywords that still do something. Stealth is gone once the monster has attacked.
+    pub fn active_keywords(&self) -> Vec<Keyword> {
+        self.keywords()
+            .iter()
+            .copied()
+            .filter(|k| *k != Keyword::Stealth || self.stealthed)
+            .collect()
     }
 
-    fn hidden(&self) -> bool {
-        self.hidden
+    pub fn is_stealthed(&self) -> bool {
+        self.stealthed
     }
 
-    fn reveal(&mut self) -> () {
-        self.hidden = false;
+    pub fn break_stealth(&mut self) {
+        self.stealthed = false;
     }
-}
 
-#[derive(Component)]
-pub struct Magic {
-    id: u32,
-    cost: i32,
-    data: MagicData,
-    hidden: bool,
-}
+    /// Whether enemy attacks have to go to this monster first.
+    pub fn is_guarding(&self) -> bool {
+        self.has_keyword(Keyword::Guard) && !self.stealthed
+    }
 
-impl Card for Magic {
-    fn data(&self) -> Box<&dyn CardData> {
-        Box::new(&self.data)
+    /// Monsters can't attack on the turn they enter the field, and only once per turn after that.
+    /// Frozen monsters can't attack at all.
+    pub fn can_attack(&self) -> bool {
+        !self.exhausted && !self.has_status(StatusKind::Freeze)
     }
 
-    fn play_card_on_hero(&self, board: &mut Board) {}
+    pub fn exhaust(&mut self) {
+        self.exhausted = true;
+    }
 
-    fn id(&self) -> u32 {
-        self.id
+    pub fn ready(&mut self) {
+        self.exhausted = false;
+        self.moved = false;
     }
 
-    fn hidden(&self) -> bool {
-        self.hidden
+    /// Monsters can move once per turn, even on the turn they enter the field, but
+    /// not while frozen.
+    pub fn can_move(&self) -> bool {
+        !self.moved && !self.has_status(StatusKind::Freeze)
     }
 
-    fn reveal(&mut self) -> () {
-        self.hidden = false;
+    /// Works `stat` out from `printed` by applying the modifiers in the order they came,
+    /// so a `Set` replaces everything before it and only later `Add`s go on top. Auras
+    /// come last, since they hold for as long as their source stands next to it.
+    fn modified(&self, stat: Stat, printed: i32) -> i32 {
+        let is_aura = |m: &&Modifier| matches!(m.duration, ModifierDuration::Aura(_));
+        let own = self.modifiers.iter().filter(|m| !is_aura(m));
+        let auras = self.modifiers.iter().filter(is_aura);
+        own.chain(auras)
+            .filter(|m| m.stat == stat)
+            .fold(printed, |value, modifier| match modifier.op {
+                ModifierOp::Set(set) => set,
+                ModifierOp::Add(add) => value + add,
+            })
     }
-}
 
-#[derive(Component)]
-pub struct MagicData {
-    name: String,
-    base_cost: i32,
-    effect: Box<Effect>,
-}
+    pub fn modifiers(&self) -> &[Modifier] {
+        &self.modifiers
+    }
+
+    pub fn cost(&self) -> i32 {
+        max(self.modified(Stat::Cost, self.data.base_cost()), 0)
+    }
+
+    /// Health before any damage taken.
+    pub fn max_health(&self) -> i32 {
+        self.modified(Stat::Health, self.data.base_health())
+    }
+
+    pub fn health(&self) -> i32 {
+        self.max_health() - self.damage_taken
+    }
+
+    /// Attack damage after modifiers and curses.
+    pub fn damage(&self) -> i32 {
+        max(
+            self.modified(Stat::Damage, self.data.base_damage())
+                - self.status_stacks(StatusKind::Curse),
+            0,
+        )
+    }
+
+    pub fn attack_type(&self) -> DamageType {
+        self.data.attack_type()
+    }
+
+    pub fn statuses(&self) -> &[Status] {
+        &self.statuses
+    }
+
+    pub fn has_status(&self, kind: StatusKind) -> bool {
+        self.statuses.iter().any(|s| s.kind == kind)
+    }
+
+    pub fn status_stacks(&self, kind: StatusKind) -> i32 {
+        self.statuses
+            .iter()
+            .find(|s| s.kind == kind)
+            .map_or(0, |s| s.stacks)
+    }
+
+    /// Applies `kind`, or adds a stack to it and refreshes its duration if already there.
+    pub fn add_status(&mut self, kind: StatusKind) {
+        match self.statuses.iter_mut().find(|s| s.kind == kind) {
+            Some(status) => {
+                status.stacks = min(status.stacks + 1, kind.max_stacks());
+                status.turns = kind.duration();
+            }
+            None => self.statuses.push(Status {
+                kind,
+                stacks: 1,
+                turns: kind.duration(),
+            }),
+        }
+    }
+
+    /// Counts one of the owner's turns off every status.
+    pub fn age_statuses(&mut self) {
+        for status in self.statuses.iter_mut() {
+            status.turns = status.turns.saturating_sub(1);
+        }
+    }
+
+    pub fn clear_expired_statuses(&mut self) {
+        self.statuses.retain(|s| s.turns > 0);
+    }
+
+    pub fn school(&self) -> MagicSchool {
+        self.data.attack_type().school()
+    }
+
+    pub fn defense(&self) -> &DefenseProfile {
+        self.data.defense()
+    }
+
+    pub fn take_damage(&mut self, amount: i32) {
+        self.damage_taken += amount;
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.health() <= 0
+    }
+
+    /// Restores health, but never above the monster's maximum health.
+    pub fn heal(&mut self, amount: i32) {
+        self.damage_taken = max(min(self.damage_taken, self.damage_taken - amount), 0);
+    }
+
+    /// Changes the modifiers with `change`. Extra maximum health adds to the current
+    /// health as well, while lost maximum health only takes away what is above the new
+    /// maximum, so losing a buff never kills a monster by itself.
+    fn change_modifiers(&mut self, change: impl FnOnce(&mut Vec<Modifier>)) {
+        let (health, max_health) = (self.health(), self.max_health());
+        change(&mut self.modifiers);
+        let new_max = self.max_health();
+        if new_max < max_health {
+            self.damage_taken = new_max - min(health, new_max);
+        }
+    }
+
+    pub fn add_modifier(&mut self, modifier: Modifier) {
+        self.change_modifiers(|modifiers| modifiers.push(modifier));
+    }
+
+    fn add_buff(&mut self, health: i32, damage: i32, duration: ModifierDuration) {
+        for (stat, amount) in [(Stat::Health, health), (Stat::Damage, damage)].iter() {
+            if *amount != 0 {
+                self.add_modifier(Modifier::new(*stat, ModifierOp::Add(*amount), duration));
+            }
+        }
+    }
+
+    pub fn buff(&mut self, health: i32, damage: i32) {
+        self.add_buff(health, damage, ModifierDuration::Permanent);
+    }
+
+    pub fn buff_until_end_of_turn(&mut self, health: i32, damage: i32) {
+        self.add_buff(health, damage, ModifierDuration::EndOfTurn);
+    }
+
+    /// Sets `stat` to `value` for good. Setting health heals the monster fully as well.
+    pub fn set_stat(&mut self, stat: Stat, value: i32) {
+        self.add_modifier(Modifier::new(
+            stat,
+            ModifierOp::Set(value),
+            ModifierDuration::Permanent,
+        ));
+        if stat == Stat::Health {
+            self.damage_taken = 0;
+        }
+    }
+
+    /// Drops the modifiers that only lasted for this turn.
+    pub fn end_turn_modifiers(&mut self) {
+        self.change_modifiers(|modifiers| {
+            modifiers.retain(|m| m.duration != ModifierDuration::EndOfTurn)
+        });
+    }
+
+    /// Replaces what the monster gets from friendly auras with `modifiers`.
+    fn set_aura_modifiers(&mut self, auras: Vec<Modifier>) {
+        let current: Vec<Modifier> = self
+            .modifiers
+            .iter()
+            .copied()
+            .filter(|m| matches!(m.duration, ModifierDuration::Aura(_)))
+            .collect();
+        if current == auras {
+            return;
+        }
+        self.change_modifiers(|modifiers| {
+            modifiers.retain(|m| !matches!(m.duration, ModifierDuration::Aura(_)));
+            modifiers.extend(auras);
+        });
+    }
+
+    /// Takes away every buff and debuff along with the card's keywords, abilities and
+    /// aura. Auras of other monsters still apply once they are refreshed. Damage and
+    /// statuses stay.
+    pub fn silence(&mut self) {
+        self.change_modifiers(|modifiers| modifiers.clear());
+        self.silenced = true;
+        self.stealthed = false;
+    }
+
+    pub fn destroy(&mut self) {
+        self.damage_taken = max(self.damage_taken, self.max_health());
+    }
+}
+
+impl Card for Monster {
+    fn play_card_on_field(&self, board: &mut Board, target: usize) {
+        board.field[target] = Some(self.clone());
+    }
+
+    fn data(&self) -> Box<&dyn CardData> {
+        Box::new(&self.data)
+    }
+
+    fn play_card_on_hero(&self, board: &mut Board) {}
+
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn hidden(&self) -> bool {
+        self.hidden
+    }
+
+    fn reveal(&mut self) -> () {
+        self.hidden = false;
+    }
+}
+
+#[derive(Clone, Component, Serialize, Deserialize)]
+pub struct Magic {
+    id: u32,
+    cost: i32,
+    data: MagicData,
+    hidden: bool,
+}
+
+impl Magic {
+    pub fn new(id: u32, data: MagicData) -> Magic {
+        Magic {
+            id,
+            cost: data.base_cost(),
+            data,
+            hidden: true,
+        }
+    }
+
+    pub fn cost(&self) -> i32 {
+        self.cost
+    }
+
+    pub fn effects(&self) -> &[Effect] {
+        self.data.effects()
+    }
+
+    pub fn abilities(&self) -> &[Ability] {
+        self.data.abilities()
+    }
+
+    pub fn lingers(&self) -> u32 {
+        self.data.lingers()
+    }
+
+    pub fn school(&self) -> MagicSchool {
+        self.data.school()
+    }
+
+    pub fn is_instant(&self) -> bool {
+        self.data.is_instant()
+    }
+
+    /// Whether the card answers another card, so it can only be played while the
+    /// stack holds one.
+    pub fn is_response_only(&self) -> bool {
+        self.data
+            .effects()
+            .iter()
+            .any(|e| e.target() == TargetSelector::Responded)
+    }
+
+    /// Whether `target` fits the card: every chosen-target effect must accept it, and
+    /// cards without one must be played without a target.
+    pub fn can_target(&self, target: Option<Target>, caster: &Board, opponent: &Board) -> bool {
+        let mut chosen = self
+            .effects()
+            .iter()
+            .filter(|e| e.target() == TargetSelector::Chosen)
+            .peekable();
+        match target {
+            None => chosen.peek().is_none(),
+            Some(t) => chosen.peek().is_some() && chosen.all(|e| e.can_target(t, caster, opponent)),
+        }
+    }
+
+    /// Whether the player has to pick a target when playing this card.
+    pub fn needs_target(&self) -> bool {
+        self.data
+            .effects()
+            .iter()
+            .any(|e| e.target() == TargetSelector::Chosen)
+    }
+}
+
+impl Card for Magic {
+    fn data(&self) -> Box<&dyn CardData> {
+        Box::new(&self.data)
+    }
+
+    fn play_card_on_hero(&self, board: &mut Board) {}
+
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn hidden(&self) -> bool {
+        self.hidden
+    }
+
+    fn reveal(&mut self) -> () {
+        self.hidden = false;
+    }
+}
+
+#[derive(Clone, Component, Serialize, Deserialize)]
+pub struct MagicData {
+    id: String,
+    name: String,
+    base_cost: i32,
+    #[serde(default)]
+    school: MagicSchool,
+    /// Instants can also be played in response to a card on the stack.
+    #[serde(default)]
+    instant: bool,
+    effects: Vec<Effect>,
+    /// Turn phase abilities the card keeps using once it has resolved, see `lingers`.
+    #[serde(default)]
+    abilities: Vec<Ability>,
+    /// How many of its owner's turns, counting the one it resolved in, a card with
+    /// abilities stays in play before going to the graveyard.
+    #[serde(default)]
+    lingers: u32,
+    /// Class cards can only go in decks of that class. Hero powers name the class
+    /// they belong to here.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "super::card_data::present"
+    )]
+    class: Option<HeroClass>,
+}
+
+impl MagicData {
+    /// The school of any damage the card deals.
+    pub fn school(&self) -> MagicSchool {
+        self.school
+    }
+
+    pub fn effects(&self) -> &[Effect] {
+        &self.effects
+    }
+
+    pub fn abilities(&self) -> &[Ability] {
+        &self.abilities
+    }
+
+    pub fn lingers(&self) -> u32 {
+        self.lingers
+    }
+
+    pub fn is_instant(&self) -> bool {
+        self.instant
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn base_cost(&self) -> i32 {
+        self.base_cost
+    }
+}
 
 impl CardData for MagicData {
     fn name(&self) -> String {
         self.name.clone()
     }
 
+    fn class(&self) -> Option<HeroClass> {
+        self.class
+    }
+
     fn as_magic(&self) -> Option<&MagicData> {
         Some(self)
     }
 }
 
-#[derive(Component)]
+/// A weapon as it is printed, which a hero equips to attack with.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WeaponData {
+    id: String,
+    name: String,
+    base_cost: i32,
+    attack: i32,
+    /// How many attacks the weapon lasts.
+    durability: i32,
+    attack_type: DamageType,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "super::card_data::present"
+    )]
+    class: Option<HeroClass>,
+}
+
+impl WeaponData {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn base_cost(&self) -> i32 {
+        self.base_cost
+    }
+
+    pub fn attack(&self) -> i32 {
+        self.attack
+    }
+
+    pub fn durability(&self) -> i32 {
+        self.durability
+    }
+
+    pub fn attack_type(&self) -> DamageType {
+        self.attack_type
+    }
+}
+
+impl CardData for WeaponData {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn class(&self) -> Option<HeroClass> {
+        self.class
+    }
+}
+
+#[derive(Clone, Component, Serialize, Deserialize)]
+pub struct Weapon {
+    id: u32,
+    cost: i32,
+    durability: i32,
+    data: WeaponData,
+    hidden: bool,
+}
+
+impl Weapon {
+    pub fn new(id: u32, data: WeaponData) -> Weapon {
+        Weapon {
+            id,
+            cost: data.base_cost(),
+            durability: data.durability(),
+            data,
+            hidden: true,
+        }
+    }
+
+    pub fn cost(&self) -> i32 {
+        self.cost
+    }
+
+    pub fn attack(&self) -> i32 {
+        self.data.attack()
+    }
+
+    /// Attacks left before the weapon breaks.
+    pub fn durability(&self) -> i32 {
+        self.durability
+    }
+
+    pub fn attack_type(&self) -> DamageType {
+        self.data.attack_type()
+    }
+
+    pub fn school(&self) -> MagicSchool {
+        self.data.attack_type().school()
+    }
+
+    /// Uses up one attack. Returns whether the weapon broke.
+    pub fn wear(&mut self) -> bool {
+        self.durability -= 1;
+        self.durability <= 0
+    }
+
+    /// The weapon as printed, with its durability back, for the graveyard.
+    pub fn reset(&self) -> Weapon {
+        Weapon::new(self.id, self.data.clone())
+    }
+}
+
+impl Card for Weapon {
+    fn data(&self) -> Box<&dyn CardData> {
+        Box::new(&self.data)
+    }
+
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn hidden(&self) -> bool {
+        self.hidden
+    }
+
+    fn reveal(&mut self) {
+        self.hidden = false;
+    }
+}
+
+pub const MAX_RESOURCES: i32 = 10;
+
+/// What a board can spend on playing cards. The maximum grows by one every turn
+/// up to `MAX_RESOURCES`, and the pool refills at the start of each turn.
+#[derive(Copy, Clone, Default, Serialize, Deserialize)]
+pub struct ResourcePool {
+    current: i32,
+    maximum: i32,
+}
+
+impl ResourcePool {
+    pub fn new() -> ResourcePool {
+        ResourcePool::default()
+    }
+
+    pub fn current(&self) -> i32 {
+        self.current
+    }
+
+    pub fn maximum(&self) -> i32 {
+        self.maximum
+    }
+
+    pub fn can_pay(&self, cost: i32) -> bool {
+        cost <= self.current
+    }
+
+    /// Takes `cost` out of the pool if there is enough in it.
+    pub fn spend(&mut self, cost: i32) -> bool {
+        if self.can_pay(cost) {
+            self.current -= cost;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn grow_and_refill(&mut self) {
+        self.maximum = min(self.maximum + 1, MAX_RESOURCES);
+        self.current = self.maximum;
+    }
+}
+
+#[derive(Clone, Component, Serialize, Deserialize)]
 pub struct Hero {
     id: u8,
     base_health: i32,
     health: i32,
     class: HeroClass,
+    #[serde(default)]
+    armor: i32,
+    /// Whether the hero power was used this turn.
+    #[serde(default)]
+    power_used: bool,
+    #[serde(default)]
+    weapon: Option<Weapon>,
+    /// Whether the hero attacked this turn.
+    #[serde(default)]
+    attacked: bool,
 }
 
 impl Hero {
@@ -340,15 +1490,95 @@ impl Hero {
             base_health: base_health,
             health: health,
             class: class,
+            armor: 0,
+            power_used: false,
+            weapon: None,
+            attacked: false,
         }
     }
 
     fn id(&self) -> u8 {
         self.id
     }
+
+    pub fn class(&self) -> HeroClass {
+        self.class
+    }
+
+    pub fn health(&self) -> i32 {
+        self.health
+    }
+
+    pub fn base_health(&self) -> i32 {
+        self.base_health
+    }
+
+    pub fn armor(&self) -> i32 {
+        self.armor
+    }
+
+    pub fn gain_armor(&mut self, amount: i32) {
+        self.armor += max(amount, 0);
+    }
+
+    /// Armor soaks up damage first, the rest comes off health. Negative damage does
+    /// nothing, it doesn't heal.
+    pub fn take_damage(&mut self, amount: i32) {
+        let amount = max(amount, 0);
+        let absorbed = min(self.armor, amount);
+        self.armor -= absorbed;
+        self.health -= amount - absorbed;
+    }
+
+    pub fn power_used(&self) -> bool {
+        self.power_used
+    }
+
+    /// Lets the hero use its power and attack again, at the start of its owner's turn.
+    pub fn ready(&mut self) {
+        self.power_used = false;
+        self.attacked = false;
+    }
+
+    pub fn weapon(&self) -> Option<&Weapon> {
+        self.weapon.as_ref()
+    }
+
+    /// Whether the hero holds a weapon and hasn't attacked with it this turn.
+    pub fn can_attack(&self) -> bool {
+        self.weapon.is_some() && !self.attacked
+    }
+
+    /// Marks the hero as having attacked and wears the weapon down. A weapon that
+    /// breaks is handed back.
+    pub fn swing(&mut self) -> Option<Weapon> {
+        self.attacked = true;
+        match self.weapon.as_mut().map(|w| w.wear()) {
+            Some(true) => self.weapon.take(),
+            _ => None,
+        }
+    }
+
+    pub fn heal(&mut self, amount: i32) {
+        self.health = max(self.health, min(self.health + amount, self.base_health));
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.health <= 0
+    }
 }
 
-#[derive(Component)]
+/// What it costs to move a monster to another slot of its field.
+pub const MOVE_COST: i32 = 1;
+
+/// How many cards a hand holds unless the match sets another limit.
+pub const MAX_HAND_SIZE: usize = 10;
+
+fn default_max_hand_size() -> usize {
+    MAX_HAND_SIZE
+}
+
+#[derive(Clone, Component, Serialize, Deserialize)]
 pub struct Board {
     id: u8,
     hero: Hero,
@@ -357,10 +1587,26 @@ pub struct Board {
     deck: Vec<CardHolder>,
     graveyard: Vec<CardHolder>,
     highlighted: (u8, i32),
+    next_card_id: u32,
+    resources: ResourcePool,
+    fatigue: i32,
+    #[serde(default = "default_max_hand_size")]
+    max_hand_size: usize,
+    /// Cards the player still has to pick from their hand and discard.
+    #[serde(default)]
+    discards: u32,
+    /// Resolved magic cards staying in play for their abilities, oldest first.
+    #[serde(default)]
+    lingering: Vec<Lingering>,
+    #[serde(skip)]
+    events: Vec<BoardEvent>,
+    #[serde(skip)]
+    logged: Vec<LogEvent>,
 }
 
 impl Board {
     pub fn new(id: u8, hero: Hero, deck: Vec<CardHolder>) -> Board {
+        let next_card_id = deck.iter().map(|c| c.id() + 1).max().unwrap_or(0);
         let mut ret = Board {
             id: id,
             hero: hero,
@@ -369,6 +1615,14 @@ impl Board {
             deck: deck,
             graveyard: vec![],
             highlighted: (3, 0),
+            next_card_id,
+            resources: ResourcePool::new(),
+            fatigue: 0,
+            max_hand_size: MAX_HAND_SIZE,
+            discards: 0,
+            lingering: vec![],
+            events: vec![],
+            logged: vec![],
         };
 
         ret
@@ -382,6 +1636,277 @@ impl Board {
         self.field.clone()
     }
 
+    pub fn monster(&self, slot: usize) -> Option<&Monster> {
+        self.field.get(slot).and_then(|m| m.as_ref())
+    }
+
+    pub fn monster_mut(&mut self, slot: usize) -> Option<&mut Monster> {
+        self.field.get_mut(slot).and_then(|m| m.as_mut())
+    }
+
+    /// Clears every field slot whose monster has no health left, from left to right,
+    /// queueing a death event for each and putting the card in the graveyard. Auras
+    /// are refreshed afterwards, and monsters that lost too much with them go as well.
+    pub fn remove_dead_monsters(&mut self) -> Vec<Monster> {
+        let mut dead = vec![];
+        loop {
+            let buried = dead.len();
+            for (index, slot) in self.field.iter_mut().enumerate() {
+                if slot.as_ref().is_some_and(|m| m.is_dead()) {
+                    if let Some(monster) = slot.take() {
+                        self.events.push(BoardEvent::Died(index, monster.clone()));
+                        dead.push(monster);
+                    }
+                }
+            }
+            for monster in dead[buried..].iter() {
+                self.send_to_graveyard(CardHolder::MonsterCard(monster.reset()));
+            }
+            self.refresh_auras();
+            if dead.len() == buried {
+                return dead;
+            }
+        }
+    }
+
+    /// The occupied slots next to `slot`, left one first.
+    pub fn neighbours(&self, slot: usize) -> Vec<usize> {
+        [slot.checked_sub(1), Some(slot + 1)]
+            .iter()
+            .flatten()
+            .copied()
+            .filter(|s| self.monster(*s).is_some())
+            .collect()
+    }
+
+    /// Works out again what every monster gets from the auras of its neighbours.
+    /// Called whenever monsters enter, leave or move around the field.
+    pub fn refresh_auras(&mut self) {
+        let auras: Vec<(usize, u32, Aura)> = self
+            .field
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, m)| {
+                let m = m.as_ref()?;
+                m.aura().map(|aura| (slot, m.id(), aura))
+            })
+            .collect();
+        for (slot, monster) in self.field.iter_mut().enumerate() {
+            let monster = match monster {
+                Some(monster) => monster,
+                None => continue,
+            };
+            let mut modifiers = vec![];
+            let nearby = auras.iter().filter(|(from, _, _)| from.abs_diff(slot) == 1);
+            for (_, source, aura) in nearby {
+                let duration = ModifierDuration::Aura(*source);
+                if aura.health != 0 {
+                    modifiers.push(Modifier::new(
+                        Stat::Health,
+                        ModifierOp::Add(aura.health),
+                        duration,
+                    ));
+                }
+                if aura.damage != 0 {
+                    modifiers.push(Modifier::new(
+                        Stat::Damage,
+                        ModifierOp::Add(aura.damage),
+                        duration,
+                    ));
+                }
+            }
+            monster.set_aura_modifiers(modifiers);
+        }
+    }
+
+    /// Drops every modifier that only lasted for this turn.
+    pub fn end_turn_modifiers(&mut self) {
+        for monster in self.monsters_mut() {
+            monster.end_turn_modifiers();
+        }
+    }
+
+    /// Hurts the monster in `slot` and queues a damage event if anything went through.
+    pub fn damage_monster(&mut self, slot: usize, amount: i32) {
+        if let Some(monster) = self.monster_mut(slot) {
+            monster.take_damage(amount);
+            if amount > 0 {
+                let hit = monster.clone();
+                self.events.push(BoardEvent::Damaged(slot, hit));
+            }
+        }
+    }
+
+    /// Hands over every queued event, oldest first.
+    pub fn take_events(&mut self) -> Vec<BoardEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Hands over every event queued for the match log, oldest first.
+    pub fn take_logged(&mut self) -> Vec<LogEvent> {
+        std::mem::take(&mut self.logged)
+    }
+
+    pub fn resources(&self) -> ResourcePool {
+        self.resources
+    }
+
+    pub fn resources_mut(&mut self) -> &mut ResourcePool {
+        &mut self.resources
+    }
+
+    pub fn monsters_mut(&mut self) -> impl Iterator<Item = &mut Monster> {
+        self.field.iter_mut().flatten()
+    }
+
+    pub fn ready_monsters(&mut self) {
+        for monster in self.monsters_mut() {
+            monster.ready();
+        }
+    }
+
+    pub fn first_free_slot(&self) -> Option<usize> {
+        self.field.iter().position(|m| m.is_none())
+    }
+
+    /// Puts a fresh copy of `data` into an empty field slot.
+    pub fn summon(&mut self, slot: usize, data: MonsterData) -> bool {
+        match self.field.get(slot) {
+            Some(None) => {
+                let mut monster = Monster::new(self.next_card_id, data);
+                monster.reveal();
+                self.next_card_id += 1;
+                self.field[slot] = Some(monster);
+                self.refresh_auras();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn take_from_hand(&mut self, card: usize) -> Option<CardHolder> {
+        if card < self.hand.len() {
+            Some(self.hand.remove(card))
+        } else {
+            None
+        }
+    }
+
+    /// Every card that left play, oldest first. Graveyards are public, so the cards
+    /// are face up.
+    pub fn graveyard(&self) -> &[CardHolder] {
+        &self.graveyard
+    }
+
+    pub fn send_to_graveyard(&mut self, mut card: CardHolder) {
+        card.reveal();
+        self.graveyard.push(card);
+    }
+
+    pub fn lingering(&self) -> &[Lingering] {
+        &self.lingering
+    }
+
+    /// Puts a magic card that has resolved in the graveyard, unless it has abilities
+    /// to linger in play with.
+    pub fn settle_magic(&mut self, magic: Magic) {
+        if magic.abilities().is_empty() || magic.lingers() == 0 {
+            self.send_to_graveyard(CardHolder::MagicCard(magic));
+        } else {
+            self.lingering.push(Lingering {
+                turns_left: magic.lingers(),
+                magic,
+            });
+        }
+    }
+
+    /// Counts down one of the owner's turns for every lingering card, at the end of
+    /// that turn. Cards whose time is up go to the graveyard.
+    pub fn age_lingering(&mut self) {
+        let (expired, lingering): (Vec<Lingering>, Vec<Lingering>) =
+            std::mem::take(&mut self.lingering)
+                .into_iter()
+                .map(|mut card| {
+                    card.turns_left -= 1;
+                    card
+                })
+                .partition(|card| card.turns_left == 0);
+        self.lingering = lingering;
+        for card in expired {
+            self.send_to_graveyard(CardHolder::MagicCard(card.magic));
+        }
+    }
+
+    fn last_buried_monster(&self) -> Option<usize> {
+        self.graveyard
+            .iter()
+            .rposition(|c| matches!(c, CardHolder::MonsterCard(_)))
+    }
+
+    /// Moves the monster in `slot` back to the hand as printed. It doesn't die, and
+    /// stays face up since everyone has seen it on the field.
+    pub fn return_to_hand(&mut self, slot: usize) -> bool {
+        match self.field.get_mut(slot).and_then(|m| m.take()) {
+            Some(monster) => {
+                let mut card = CardHolder::MonsterCard(monster.reset());
+                card.reveal();
+                self.add_to_hand(card);
+                self.refresh_auras();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves the latest card of the graveyard back to the hand.
+    pub fn recover_from_graveyard(&mut self) -> bool {
+        match self.graveyard.pop() {
+            Some(card) => {
+                self.add_to_hand(card);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Puts the latest monster of the graveyard back into the empty `slot`.
+    pub fn resurrect(&mut self, slot: usize) -> bool {
+        if !matches!(self.field.get(slot), Some(None)) {
+            return false;
+        }
+        let index = match self.last_buried_monster() {
+            Some(index) => index,
+            None => return false,
+        };
+        if let CardHolder::MonsterCard(monster) = self.graveyard.remove(index) {
+            let mut monster = monster.reset();
+            monster.reveal();
+            self.field[slot] = Some(monster);
+            self.refresh_auras();
+        }
+        true
+    }
+
+    /// Takes the monster in `slot` out of the game without it dying.
+    pub fn exile_monster(&mut self, slot: usize) -> Option<Monster> {
+        let exiled = self.field.get_mut(slot).and_then(|m| m.take());
+        self.refresh_auras();
+        exiled
+    }
+
+    /// Removes every card in the graveyard from the game and returns how many there were.
+    pub fn exile_graveyard(&mut self) -> usize {
+        std::mem::take(&mut self.graveyard).len()
+    }
+
+    pub fn hero(&self) -> &Hero {
+        &self.hero
+    }
+
+    pub fn hero_mut(&mut self) -> &mut Hero {
+        &mut self.hero
+    }
+
     pub fn hand(&self) -> &Vec<CardHolder> {
         &self.hand
     }
@@ -394,36 +1919,261 @@ impl Board {
         self.highlighted = new_highlighted;
     }
 
+    /// Drawing from an empty deck deals fatigue damage to the hero instead,
+    /// one more than the last time.
     pub fn draw_card(&mut self) {
         match self.deck.pop() {
-            Some(mut x) => {
-                if self.id == 0 {
-                    x.reveal();
-                }
-                self.hand.push(x)
+            Some(x) => self.add_to_hand(x),
+            None => {
+                self.fatigue += 1;
+                self.hero.take_damage(self.fatigue);
+            }
+        }
+    }
+
+    /// Puts `card` back into the deck at a random position.
+    pub fn shuffle_into_deck(&mut self, card: CardHolder, rng: &mut RandomNumberGenerator) {
+        let position = rng.range(0, self.deck.len() + 1);
+        self.deck.insert(position, card);
+    }
+
+    pub fn fatigue(&self) -> i32 {
+        self.fatigue
+    }
+
+    pub fn max_hand_size(&self) -> usize {
+        self.max_hand_size
+    }
+
+    pub fn set_max_hand_size(&mut self, max_hand_size: usize) {
+        self.max_hand_size = max_hand_size;
+    }
+
+    /// How many cards the player has to discard before anyone may act again.
+    pub fn discards_pending(&self) -> u32 {
+        self.discards
+    }
+
+    /// Makes the player discard `count` more cards of their choice, as many as they hold.
+    pub fn require_discards(&mut self, count: u32) {
+        self.discards = min(self.discards + count, self.hand.len() as u32);
+    }
+
+    /// Discards the card at `card` in hand towards the discards the player owes.
+    pub fn discard(&mut self, card: usize) -> Result<(), ActionError> {
+        if self.discards == 0 {
+            return Err(ActionError::NothingToDiscard);
+        }
+        let card = self
+            .take_from_hand(card)
+            .ok_or(ActionError::CardNotInHand(card))?;
+        self.discards = min(self.discards - 1, self.hand.len() as u32);
+        self.put_discarded(card);
+        Ok(())
+    }
+
+    /// Discards up to `count` cards picked at random from the hand.
+    pub fn discard_random(&mut self, count: u32, rng: &mut RandomNumberGenerator) {
+        for _ in 0..count {
+            if self.hand.is_empty() {
+                break;
             }
-            _ => {}
+            let card = self.hand.remove(rng.range(0, self.hand.len()));
+            self.put_discarded(card);
+        }
+        self.discards = min(self.discards, self.hand.len() as u32);
+    }
+
+    fn put_discarded(&mut self, mut card: CardHolder) {
+        card.reveal();
+        self.logged.push(LogEvent::Discarded(card.clone()));
+        self.graveyard.push(card);
+    }
+
+    /// The human player's hand is shown face up. Cards the opponent gets back from the
+    /// graveyard stay face up too, since everyone saw them there. A card that finds the
+    /// hand full is burned: it goes face up into the graveyard instead.
+    fn add_to_hand(&mut self, mut card: CardHolder) {
+        if self.hand.len() >= self.max_hand_size {
+            card.reveal();
+            self.logged.push(LogEvent::Burned(card.clone()));
+            self.graveyard.push(card);
+            return;
+        }
+        if self.id == 0 {
+            card.reveal();
         }
+        self.hand.push(card)
     }
 
-    pub fn play_card(&mut self, card: usize, target: Target) -> Option<CardHolder> {
+    /// The field slot a monster played at `target` goes to. Monsters only go on their
+    /// owner's field: into the slot picked, which has to be empty, or into the leftmost
+    /// free one when aimed at the own hero or at nothing.
+    fn monster_slot(&self, target: Option<Target>) -> Result<usize, ActionError> {
         match target {
-            Target::Friendly(x) => match x {
-                TargetType::Hero => None,
-                TargetType::Monster(i) => {
-                    let field_slot = self.field[i].clone();
-                    match field_slot {
-                        None => {
-                            if let CardHolder::MonsterCard(chosen_card) = &self.hand.remove(card) {
-                                chosen_card.play_card_on_field(self, i);
-                            }
-                            None
-                        }
-                        Some(_) => None,
-                    }
-                }
+            Some(Target::Friendly(TargetType::Monster(slot))) => match self.field.get(slot) {
+                Some(None) => Ok(slot),
+                Some(Some(_)) => Err(ActionError::SlotOccupied(slot)),
+                None => Err(ActionError::InvalidTarget(Target::Friendly(
+                    TargetType::Monster(slot),
+                ))),
             },
-            _ => None,
+            Some(Target::Friendly(TargetType::Hero)) | None => {
+                self.first_free_slot().ok_or(ActionError::NoFreeSlot)
+            }
+            Some(target) => Err(ActionError::InvalidTarget(target)),
+        }
+    }
+
+    /// Checks that the card at `card` in hand can be paid for and played at `target`,
+    /// with `opponent` the opposing board.
+    pub fn check_play(
+        &self,
+        opponent: &Board,
+        card: usize,
+        target: Option<Target>,
+    ) -> Result<(), ActionError> {
+        let holder = self
+            .hand
+            .get(card)
+            .ok_or(ActionError::CardNotInHand(card))?;
+        self.check_cost(holder.cost())?;
+        match holder {
+            CardHolder::MonsterCard(_) => self.monster_slot(target).map(|_| ()),
+            CardHolder::MagicCard(magic) => self.check_magic_target(opponent, magic, target),
+            CardHolder::WeaponCard(_) => match target {
+                None | Some(Target::Friendly(TargetType::Hero)) => Ok(()),
+                Some(t) => Err(ActionError::InvalidTarget(t)),
+            },
+        }
+    }
+
+    fn check_cost(&self, cost: i32) -> Result<(), ActionError> {
+        if self.resources.can_pay(cost) {
+            Ok(())
+        } else {
+            Err(ActionError::NotEnoughResources {
+                cost,
+                available: self.resources.current(),
+            })
+        }
+    }
+
+    fn check_magic_target(
+        &self,
+        opponent: &Board,
+        magic: &Magic,
+        target: Option<Target>,
+    ) -> Result<(), ActionError> {
+        match target {
+            None if magic.needs_target() => Err(ActionError::TargetRequired),
+            Some(_) if !magic.needs_target() => Err(ActionError::TargetNotAllowed),
+            Some(t) if !magic.can_target(target, self, opponent) => {
+                Err(ActionError::InvalidTarget(t))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that `power`, the power of this board's hero, hasn't been used this
+    /// turn and can be paid for and used at `target`.
+    pub fn check_hero_power(
+        &self,
+        opponent: &Board,
+        power: &Magic,
+        target: Option<Target>,
+    ) -> Result<(), ActionError> {
+        if self.hero.power_used {
+            return Err(ActionError::PowerUsed);
+        }
+        self.check_cost(power.cost())?;
+        self.check_magic_target(opponent, power, target)
+    }
+
+    /// Pays for `power` and marks it used for the turn, if it can be used at `target`.
+    /// Resolving its effects is up to the caller.
+    pub fn use_hero_power(
+        &mut self,
+        opponent: &Board,
+        power: &Magic,
+        target: Option<Target>,
+    ) -> Result<(), ActionError> {
+        self.check_hero_power(opponent, power, target)?;
+        self.resources.spend(power.cost());
+        self.hero.power_used = true;
+        Ok(())
+    }
+
+    /// Checks that the monster in `slot` may move to `to`, another slot of the same field.
+    pub fn check_move(&self, slot: usize, to: usize) -> Result<(), ActionError> {
+        let monster = self.monster(slot).ok_or(ActionError::NoMonster(slot))?;
+        if to == slot || to >= self.field.len() {
+            return Err(ActionError::InvalidTarget(Target::Friendly(
+                TargetType::Monster(to),
+            )));
+        }
+        if !monster.can_move() {
+            return Err(ActionError::CannotMove(slot));
+        }
+        if self.monster(to).is_some_and(|m| !m.can_move()) {
+            return Err(ActionError::CannotMove(to));
+        }
+        self.check_cost(MOVE_COST)
+    }
+
+    /// Pays `MOVE_COST` to move the monster in `slot` to `to`. A monster already
+    /// standing there swaps places with it, which uses up its move for the turn too,
+    /// so it has to be able to move as well.
+    pub fn move_monster(&mut self, slot: usize, to: usize) -> Result<(), ActionError> {
+        self.check_move(slot, to)?;
+        self.resources.spend(MOVE_COST);
+        self.field.swap(slot, to);
+        for moved in [slot, to] {
+            if let Some(monster) = self.monster_mut(moved) {
+                monster.moved = true;
+            }
+        }
+        self.refresh_auras();
+        Ok(())
+    }
+
+    /// Pays for the card at `card` in hand and takes it out of the hand, if it can be
+    /// played at `target`. Monsters go straight onto the field and weapons into the
+    /// hero's hands, magic cards are handed back to be put on the stack.
+    pub fn play_card(
+        &mut self,
+        opponent: &Board,
+        card: usize,
+        target: Option<Target>,
+    ) -> Result<Played, ActionError> {
+        self.check_play(opponent, card, target)?;
+        match self.hand.remove(card) {
+            CardHolder::MonsterCard(monster) => {
+                let slot = self.monster_slot(target)?;
+                self.resources.spend(monster.cost());
+                monster.play_card_on_field(self, slot);
+                self.refresh_auras();
+                self.events.push(BoardEvent::Played(slot));
+                Ok(Played::Monster(slot))
+            }
+            CardHolder::MagicCard(mut magic) => {
+                self.resources.spend(magic.cost());
+                magic.reveal();
+                Ok(Played::Magic(magic))
+            }
+            CardHolder::WeaponCard(mut weapon) => {
+                self.resources.spend(weapon.cost());
+                weapon.reveal();
+                self.equip(weapon);
+                Ok(Played::Weapon)
+            }
+        }
+    }
+
+    /// Puts `weapon` in the hero's hands. The one it held before goes to the graveyard.
+    pub fn equip(&mut self, weapon: Weapon) {
+        if let Some(old) = self.hero.weapon.replace(weapon) {
+            self.send_to_graveyard(CardHolder::WeaponCard(old.reset()));
         }
     }
 
@@ -432,6 +2182,35 @@ impl Board {
     }
 }
 
+/// A magic card staying in play after it resolved, using its abilities at its owner's
+/// turn phases.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Lingering {
+    magic: Magic,
+    turns_left: u32,
+}
+
+impl Lingering {
+    pub fn magic(&self) -> &Magic {
+        &self.magic
+    }
+
+    /// How many more of the owner's turns the card stays, counting the current one.
+    pub fn turns_left(&self) -> u32 {
+        self.turns_left
+    }
+}
+
+/// What became of a card played from hand.
+pub enum Played {
+    /// The monster now stands in this field slot.
+    Monster(usize),
+    /// The magic card has been paid for and still has to resolve.
+    Magic(Magic),
+    /// The weapon is in the hero's hands.
+    Weapon,
+}
+
 #[derive(Component)]
 pub struct Game {
     players: (Board, Board),
@@ -451,13 +2230,88 @@ impl Game {
     }
 }
 
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Turn {
     player: u8,
     phase: TurnPhase,
+    number: u32,
 }
 
+impl Turn {
+    pub fn new(player: u8) -> Turn {
+        Turn {
+            player,
+            phase: TurnPhase::PrePlay,
+            number: 1,
+        }
+    }
+
+    pub fn player(&self) -> u8 {
+        self.player
+    }
+
+    pub fn phase(&self) -> TurnPhase {
+        self.phase
+    }
+
+    pub fn number(&self) -> u32 {
+        self.number
+    }
+
+    pub fn set_phase(&mut self, phase: TurnPhase) {
+        self.phase = phase;
+    }
+
+    /// The opening phase of the other player's turn.
+    pub fn next(&self) -> Turn {
+        Turn {
+            player: 1 - self.player,
+            phase: TurnPhase::PrePlay,
+            number: self.number + 1,
+        }
+    }
+}
+
+/// The seed the match's random number generator started from. The same seed and the
+/// same actions always play out the same match.
+#[derive(Copy, Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct MatchSeed(pub u64);
+
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum MatchStatus {
+    Ongoing,
+    Won(u8),
+    Drawn,
+}
+
+impl MatchStatus {
+    pub fn is_over(&self) -> bool {
+        *self != MatchStatus::Ongoing
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum TurnPhase {
     PrePlay,
     InPlay,
     PostPlay,
 }
+
+/// Marks the entities written to a save file.
+pub struct SerializeMe;
+
+/// Carries the world resources through a save file, since only components get serialized.
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct SerializationHelper {
+    pub turn: Turn,
+    pub status: MatchStatus,
+    pub rng: RandomNumberGenerator,
+    #[serde(default)]
+    pub seed: MatchSeed,
+    #[serde(default)]
+    pub stack: EffectStack,
+    #[serde(default)]
+    pub mulligan: Mulligan,
+    #[serde(default)]
+    pub log: MatchLog,
+}
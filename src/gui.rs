@@ -1,12 +1,18 @@
-use crate::entities::CardHolder;
-
-use super::entities::{Board, Card, CardData, Monster, MonsterData};
+use super::player_system::{
+    Selection, ENEMY_FIELD_ROW, ENEMY_HAND_ROW, PLAYER_FIELD_ROW, PLAYER_HAND_ROW,
+};
 use rltk::{Rltk, RGB};
 use specs::prelude::*;
-use std::any::Any;
-use std::io;
-use std::io::*;
-use std::str::FromStr;
+use std::cmp::Ordering;
+use tcg_project::card_data::CardDatabase;
+use tcg_project::entities::{
+    Affinity, Board, Card, CardHolder, EffectKind, Magic, MagicSchool, MatchSeed, MatchStatus,
+    Monster, TargetSelector, Turn, Weapon, MAGIC_SCHOOLS, MAX_RESOURCES, MOVE_COST,
+};
+use tcg_project::match_cycle;
+use tcg_project::match_log::MatchLog;
+use tcg_project::mulligan::Mulligan;
+use tcg_project::stack::EffectStack;
 
 const ENEMY_RESOURCES: u8 = 0;
 const ENEMY_HAND: u8 = ENEMY_RESOURCES + 1;
@@ -22,18 +28,24 @@ const HIGHLIGHTED_TEXT: u8 = PLAYER_RESOURCES + 1;
 
 const FIELD_SLOTS: u8 = 5;
 
+/// How many of the latest match log entries are shown at the bottom of the screen.
+const LOG_LINES: u8 = 8;
+
 const MAX_HIGHLIGHTED_Y: u8 = 3;
 const PLAYER_HAND_HIGHLIGHT_INDEX: u8 = MAX_HIGHLIGHTED_Y;
 
 pub fn draw_filled_board(ecs: &World, ctx: &mut Rltk) {
-    let entities = ecs.entities();
     let boards = ecs.read_storage::<Board>();
 
-    for (vert_pos, (entity, board)) in (&entities, &boards).join().enumerate() {
+    for board in (&boards).join() {
+        let vert_pos = match board.id() {
+            0 => 1,
+            _ => 0,
+        };
         let mut hori_pos: u8 = 0;
         for slot in board.field() {
             match slot {
-                Some(monster) => draw_monster(ctx, &monster, vert_pos as u8, hori_pos),
+                Some(monster) => draw_monster(ctx, &monster, vert_pos, hori_pos),
                 None => {}
             }
             hori_pos += 1;
@@ -65,62 +77,167 @@ pub fn draw_monster(ctx: &mut Rltk, monster: &Monster, vert_pos: u8, hori_pos: u
     assert!(vert_pos <= 2);
     assert!(hori_pos <= 4);
     assert!(vert_pos != 2 || hori_pos == 2);
-    if let Some(monster_data) = monster.data().as_monster() {
-        let calculated_vert_post = match vert_pos {
-            0 => ENEMY_BOARD + 2,
-            1 => PLAYER_BOARD + 1,
-            2 => HIGHLIGHTED_CARD + 1,
-            _ => 0,
-        };
-        let mut offset = 0;
+    let data = monster.data();
+    let monster_data = match data.as_monster() {
+        Some(data) => data,
+        None => return,
+    };
+    let calculated_vert_post = match vert_pos {
+        0 => ENEMY_BOARD + 2,
+        1 => PLAYER_BOARD + 1,
+        2 => HIGHLIGHTED_CARD + 1,
+        _ => 0,
+    };
+    let mut offset = 0;
+
+    ctx.print_color(
+        1 + CARD_WIDTH * hori_pos,
+        calculated_vert_post + offset,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+        monster.data().name(),
+    );
+    draw_statuses(ctx, monster, hori_pos, calculated_vert_post + 1);
+    offset += 2;
 
+    if vert_pos == 2 {
+        let mut cost_line = monster.cost().to_string();
+        for keyword in monster.active_keywords() {
+            cost_line = format!("{} {}", cost_line, keyword.name());
+        }
+        cost_line.truncate(CARD_WIDTH as usize - 1);
         ctx.print_color(
             1 + CARD_WIDTH * hori_pos,
             calculated_vert_post + offset,
             RGB::named(rltk::WHITE),
             RGB::named(rltk::BLACK),
-            monster.data().name(),
+            cost_line,
         );
-        offset += 2;
+        offset += 1;
+    }
 
-        if vert_pos == 2 {
-            ctx.print_color(
-                1 + CARD_WIDTH * hori_pos,
-                calculated_vert_post + offset,
-                RGB::named(rltk::WHITE),
-                RGB::named(rltk::BLACK),
-                monster.cost(),
-            );
-            offset += 1;
-        }
+    // Buffed maximum health shows in green, lowered in grey.
+    let health_string = format!("{}/{}", monster.health(), monster.max_health());
+    let health_color = match monster.max_health().cmp(&monster_data.base_health()) {
+        Ordering::Greater => rltk::GREEN,
+        Ordering::Less => rltk::GREY,
+        Ordering::Equal => rltk::RED,
+    };
+    ctx.print_color(
+        1 + CARD_WIDTH * hori_pos,
+        calculated_vert_post + offset,
+        RGB::named(health_color),
+        RGB::named(rltk::BLACK),
+        health_string,
+    );
+    offset += 1;
 
-        let health_string = format!("{}/{}", monster.health(), monster_data.base_health());
+    let damage_string = format!(
+        "{}, {}",
+        monster.damage(),
+        monster_data.attack_type().name()
+    );
+    ctx.print_color(
+        1 + CARD_WIDTH * hori_pos,
+        calculated_vert_post + offset,
+        RGB::named(monster_data.attack_type().color()),
+        RGB::named(rltk::BLACK),
+        damage_string,
+    );
+}
+
+/// Tags for the monster's statuses, written over the divider under its name.
+fn draw_statuses(ctx: &mut Rltk, monster: &Monster, hori_pos: u8, y: u8) {
+    let end = CARD_WIDTH * (hori_pos + 1);
+    let mut x = 1 + CARD_WIDTH * hori_pos;
+    for status in monster.statuses() {
+        let tag = match status.stacks() {
+            1 => status.kind().tag().to_string(),
+            n => format!("{}{}", status.kind().tag(), n),
+        };
+        if x + tag.len() as u8 > end {
+            break;
+        }
         ctx.print_color(
-            1 + CARD_WIDTH * hori_pos,
-            calculated_vert_post + offset,
-            RGB::named(rltk::RED),
+            x,
+            y,
+            RGB::named(status.kind().school().color()),
             RGB::named(rltk::BLACK),
-            health_string,
+            &tag,
         );
-        offset += 1;
+        x += tag.len() as u8 + 1;
+    }
+}
 
-        let damage_string = format!(
-            "{}, {}",
-            monster.damage(),
-            monster_data.attack_type().name()
-        );
+/// Magic cards are only ever shown in the highlighted card panel.
+pub fn draw_magic(ctx: &mut Rltk, magic: &Magic, hori_pos: u8) {
+    let x = 1 + CARD_WIDTH * hori_pos;
+    let y = HIGHLIGHTED_CARD + 1;
+
+    ctx.print_color(
+        x,
+        y,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+        magic.data().name(),
+    );
+    ctx.print_color(
+        x,
+        y + 2,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+        magic.cost(),
+    );
+    for (offset, effect) in magic.effects().iter().take(2).enumerate() {
+        let mut text = effect.describe();
+        text.truncate(CARD_WIDTH as usize - 1);
         ctx.print_color(
-            1 + CARD_WIDTH * hori_pos,
-            calculated_vert_post + offset,
-            RGB::named(monster_data.attack_type().color()),
+            x,
+            y + 3 + offset as u8,
+            RGB::named(rltk::MAGENTA),
             RGB::named(rltk::BLACK),
-            damage_string,
+            text,
         );
-    } else {
-        panic!("Expected monster card!");
     }
 }
 
+/// Weapons, like magic cards, only show up in the highlighted card panel.
+pub fn draw_weapon(ctx: &mut Rltk, weapon: &Weapon, hori_pos: u8) {
+    let x = 1 + CARD_WIDTH * hori_pos;
+    let y = HIGHLIGHTED_CARD + 1;
+
+    ctx.print_color(
+        x,
+        y,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+        weapon.data().name(),
+    );
+    ctx.print_color(
+        x,
+        y + 2,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+        weapon.cost(),
+    );
+    ctx.print_color(
+        x,
+        y + 3,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        format!("ATK {} DUR {}", weapon.attack(), weapon.durability()),
+    );
+    let mut attack_type = weapon.attack_type().name();
+    attack_type.truncate(CARD_WIDTH as usize - 1);
+    ctx.print_color(
+        x,
+        y + 4,
+        RGB::named(rltk::MAGENTA),
+        RGB::named(rltk::BLACK),
+        attack_type,
+    );
+}
+
 pub fn draw_template_highlighted_card(ctx: &mut Rltk) {
     ctx.print_color(
         32,
@@ -171,7 +288,8 @@ pub fn draw_highlighted_card(ctx: &mut Rltk, board: &Board) {
     } else {
         match c {
             CardHolder::MonsterCard(m) => draw_monster(ctx, m, 2, 2),
-            _ => {}
+            CardHolder::MagicCard(m) => draw_magic(ctx, m, 2),
+            CardHolder::WeaponCard(w) => draw_weapon(ctx, w, 2),
         }
     }
 }
@@ -234,8 +352,71 @@ pub fn draw_empty_board(ecs: &World, ctx: &mut Rltk, id: u8) {
 
     let entities = ecs.entities();
     let boards = ecs.read_storage::<Board>();
+    let db = ecs.fetch::<CardDatabase>();
     const WIDTH: i32 = 16;
 
+    for (_, board) in (&entities, &boards).join().filter(|x| x.1.id() == id) {
+        let hero = board.hero();
+        let health = if hero.armor() > 0 {
+            format!(
+                "HERO {}/{} +{}",
+                hero.health(),
+                hero.base_health(),
+                hero.armor()
+            )
+        } else {
+            format!("HERO {}/{}", hero.health(), hero.base_health())
+        };
+        ctx.print_color(
+            48,
+            cards_remaining_y,
+            RGB::named(rltk::RED),
+            RGB::named(rltk::BLACK),
+            health,
+        );
+        if let Some(power) = db.hero_power(hero.class()) {
+            let key = if id == 0 { "[H] " } else { "" };
+            let (text, color) = if hero.power_used() {
+                (
+                    format!("{} USED", power.data().name().to_uppercase()),
+                    rltk::GREY,
+                )
+            } else {
+                let name = power.data().name().to_uppercase();
+                (format!("{}{} ({})", key, name, power.cost()), rltk::CYAN)
+            };
+            ctx.print_color(
+                20,
+                cards_remaining_y,
+                RGB::named(color),
+                RGB::named(rltk::BLACK),
+                text,
+            );
+        }
+        ctx.print_color(
+            64,
+            cards_remaining_y,
+            RGB::named(rltk::GREY),
+            RGB::named(rltk::BLACK),
+            format!("GRAVE {}", board.graveyard().len()),
+        );
+        if let Some(weapon) = hero.weapon() {
+            let key = if id == 0 { "[W]" } else { "WPN" };
+            let color = if hero.can_attack() {
+                rltk::YELLOW
+            } else {
+                rltk::GREY
+            };
+            ctx.print_color(
+                73,
+                cards_remaining_y,
+                RGB::named(color),
+                RGB::named(rltk::BLACK),
+                format!("{} {}/{}", key, weapon.attack(), weapon.durability()),
+            );
+        }
+    }
+
     for (_, board) in (&entities, &boards).join().filter(|x| x.1.id() == 0) {
         let highlighted = board.highlighted();
         match highlighted.0 {
@@ -267,16 +448,17 @@ pub fn draw_empty_board(ecs: &World, ctx: &mut Rltk, id: u8) {
 }
 
 pub fn display_hand(ecs: &World, ctx: &mut Rltk) {
-    let entities = ecs.entities();
     let boards = ecs.read_storage::<Board>();
-    let player1_highlighted = boards
-        .get(entities.entity(0))
-        .expect("No players")
-        .highlighted();
+    let player1_highlighted = match (&boards).join().find(|b| b.id() == 0) {
+        Some(board) => board.highlighted(),
+        None => return,
+    };
+    let marked = &ecs.fetch::<Selection>().marked;
 
-    for (entity, board) in (&entities, &boards).join() {
+    for board in (&boards).join() {
         let mut printed_now = 0;
         let mut printed_next = 0;
+        let mut unprinted = 0;
         let hand = board.hand();
         let id = board.id();
         let hand_index: u8 = match id {
@@ -309,18 +491,391 @@ pub fn display_hand(ecs: &World, ctx: &mut Rltk) {
             if index > 0 {
                 card_name.insert_str(0, " | ");
             }
+            // Names that don't fit are counted instead, keeping room for the count.
+            printed_next += card_name.len() as u32;
+            if unprinted > 0 || printed_next > 70 {
+                unprinted += 1;
+                continue;
+            }
+            // Cards marked to go back in the mulligan show in red.
+            let color = if id == 0 && marked.contains(&index) {
+                rltk::RED
+            } else {
+                rltk::WHITE
+            };
             ctx.print_color(
                 printed_now,
                 y,
-                RGB::named(rltk::WHITE),
+                RGB::named(color),
                 RGB::named(rltk::BLACK),
                 &card_name,
             );
-            printed_next += card_name.len() as u32;
-            if printed_next > 80 {
-                break;
-            }
             printed_now = printed_next;
         }
+        if unprinted > 0 {
+            ctx.print_color(
+                printed_now,
+                y,
+                RGB::named(rltk::GREY),
+                RGB::named(rltk::BLACK),
+                format!(" | +{} MORE", unprinted),
+            );
+        }
     }
 }
+
+pub fn draw_selection_hint(ecs: &World, ctx: &mut Rltk) {
+    let selection = ecs.fetch::<Selection>();
+    if let Some(error) = &selection.file_error {
+        ctx.print_color(
+            0,
+            HIGHLIGHTED_TEXT,
+            RGB::named(rltk::RED),
+            RGB::named(rltk::BLACK),
+            error,
+        );
+    } else if let Some(error) = selection.error {
+        ctx.print_color(
+            0,
+            HIGHLIGHTED_TEXT,
+            RGB::named(rltk::RED),
+            RGB::named(rltk::BLACK),
+            format!("Not allowed: {}", error),
+        );
+    } else if let Some(source) = selection.source {
+        let hint = if source.0 == PLAYER_FIELD_ROW {
+            format!(
+                "Choose a target, or an own slot to move there for {}, Esc to cancel",
+                MOVE_COST
+            )
+        } else {
+            "Choose a target (enemy hand row for the enemy hero), Esc to cancel".to_string()
+        };
+        ctx.print_color(
+            0,
+            HIGHLIGHTED_TEXT,
+            RGB::named(rltk::YELLOW),
+            RGB::named(rltk::BLACK),
+            hint,
+        );
+    }
+}
+
+pub fn draw_turn(ecs: &World, ctx: &mut Rltk) {
+    let turn = ecs.fetch::<Turn>();
+    let mulligan = ecs.fetch::<Mulligan>();
+    let text = if mulligan.is_waiting(0) {
+        "MULLIGAN - [ENTER] MARK CARDS TO REPLACE  [E] KEEP THE REST".to_string()
+    } else if mulligan.is_open() {
+        "MULLIGAN - WAITING FOR THE ENEMY".to_string()
+    } else if let Some(player) = match_cycle::discarding_player(ecs) {
+        if player == 0 {
+            "DISCARD - [ENTER] DISCARD THE HIGHLIGHTED CARD".to_string()
+        } else {
+            "DISCARD - WAITING FOR THE ENEMY".to_string()
+        }
+    } else if turn.player() == 0 {
+        format!(
+            "TURN {} - YOUR TURN - [E] END TURN  [G] GRAVEYARD  [S] SAVE  [L] LOAD",
+            turn.number()
+        )
+    } else {
+        format!("TURN {} - ENEMY TURN", turn.number())
+    };
+    ctx.print_color(
+        0,
+        HIGHLIGHTED_TEXT + 1,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+        text,
+    );
+}
+
+/// Lists the cards waiting on the stack, top first, and asks the player to answer
+/// or pass while they hold priority.
+pub fn draw_stack(ecs: &World, ctx: &mut Rltk) {
+    let stack = ecs.fetch::<EffectStack>();
+    if stack.is_empty() {
+        return;
+    }
+    let y = HIGHLIGHTED_TEXT + 5;
+    let prompt = if stack.priority() == Some(0) {
+        "STACK - PLAY AN INSTANT OR [P] PASS"
+    } else {
+        "STACK - WAITING FOR THE ENEMY"
+    };
+    ctx.print_color(
+        0,
+        y,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        prompt,
+    );
+    for (row, entry) in stack.entries().iter().rev().enumerate() {
+        let (owner, color) = if entry.controller() == 0 {
+            ("YOU", rltk::GREEN)
+        } else {
+            ("ENEMY", rltk::RED)
+        };
+        ctx.print_color(
+            2,
+            y + 1 + row as u8,
+            RGB::named(color),
+            RGB::named(rltk::BLACK),
+            format!("{}: {}", owner, entry.describe()),
+        );
+    }
+}
+
+/// Lists the graveyard the player opened with G, the last card to arrive first.
+pub fn draw_graveyard(ecs: &World, ctx: &mut Rltk) {
+    let id = match ecs.fetch::<Selection>().browsing {
+        Some(id) => id,
+        None => return,
+    };
+    let boards = ecs.read_storage::<Board>();
+    let board = match (&boards).join().find(|b| b.id() == id) {
+        Some(board) => board,
+        None => return,
+    };
+    let x = 40;
+    let y = HIGHLIGHTED_TEXT + 5;
+    let owner = if id == 0 { "YOUR" } else { "ENEMY" };
+    ctx.print_color(
+        x,
+        y,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        format!(
+            "{} GRAVEYARD ({}) - [G] NEXT",
+            owner,
+            board.graveyard().len()
+        ),
+    );
+    for (row, card) in board.graveyard().iter().rev().enumerate() {
+        let text = match card {
+            CardHolder::MonsterCard(m) => {
+                format!("{} {}/{}", m.data().name(), m.damage(), m.health())
+            }
+            CardHolder::MagicCard(m) => format!("{} ({})", m.data().name(), m.school().name()),
+            CardHolder::WeaponCard(w) => {
+                format!("{} {}/{}", w.data().name(), w.attack(), w.durability())
+            }
+        };
+        ctx.print_color(
+            x + 2,
+            y + 1 + row as u8,
+            RGB::named(rltk::WHITE),
+            RGB::named(rltk::BLACK),
+            text,
+        );
+    }
+}
+
+pub fn draw_resources(ecs: &World, ctx: &mut Rltk) {
+    let boards = ecs.read_storage::<Board>();
+
+    for board in (&boards).join() {
+        let y = match board.id() {
+            0 => PLAYER_RESOURCES,
+            _ => ENEMY_RESOURCES,
+        };
+        let resources = board.resources();
+        let mut gauge = String::new();
+        for i in 0..MAX_RESOURCES {
+            gauge.push(if i < resources.current() {
+                '*'
+            } else if i < resources.maximum() {
+                'o'
+            } else {
+                '.'
+            });
+        }
+        ctx.print_color(
+            0,
+            y,
+            RGB::named(rltk::CYAN),
+            RGB::named(rltk::BLACK),
+            format!(
+                "MANA {}/{} [{}]  HAND {}/{}",
+                resources.current(),
+                resources.maximum(),
+                gauge,
+                board.hand().len(),
+                board.max_hand_size()
+            ),
+        );
+    }
+}
+
+pub fn draw_match_status(ecs: &World, ctx: &mut Rltk) {
+    let text = match *ecs.fetch::<MatchStatus>() {
+        MatchStatus::Ongoing => return,
+        MatchStatus::Won(0) => "GAME OVER - YOU WIN",
+        MatchStatus::Won(_) => "GAME OVER - YOU LOSE",
+        MatchStatus::Drawn => "GAME OVER - DRAW",
+    };
+    ctx.print_color(
+        0,
+        HIGHLIGHTED_TEXT + 2,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        text,
+    );
+}
+
+/// The latest entries of the match log at the bottom of the screen, newest last.
+pub fn draw_log(ecs: &World, ctx: &mut Rltk) {
+    let log = ecs.fetch::<MatchLog>();
+    if log.entries().is_empty() {
+        return;
+    }
+    let y = 79 - LOG_LINES;
+    ctx.print_color(
+        0,
+        y,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        "LOG",
+    );
+    let shown = log.entries().len().saturating_sub(LOG_LINES as usize);
+    for (row, entry) in log.entries()[shown..].iter().enumerate() {
+        let (owner, color) = if entry.player() == 0 {
+            ("YOU", rltk::GREEN)
+        } else {
+            ("ENEMY", rltk::RED)
+        };
+        ctx.print_color(
+            2,
+            y + 1 + row as u8,
+            RGB::named(color),
+            RGB::named(rltk::BLACK),
+            format!(
+                "TURN {} {}: {}",
+                entry.turn(),
+                owner,
+                entry.event().describe()
+            ),
+        );
+    }
+}
+
+/// The seed of the match in the corner, to quote when something goes wrong.
+pub fn draw_seed(ecs: &World, ctx: &mut Rltk) {
+    let text = format!("SEED {}", ecs.fetch::<MatchSeed>().0);
+    ctx.print_color(
+        81 - text.len() as u32,
+        HIGHLIGHTED_TEXT + 2,
+        RGB::named(rltk::GREY),
+        RGB::named(rltk::BLACK),
+        text,
+    );
+}
+
+/// The damage the selected attacker or targeted spell deals, and of which school.
+fn selected_hit(board: &Board, source: (u8, i32)) -> Option<(i32, MagicSchool)> {
+    match source.0 {
+        PLAYER_FIELD_ROW => board
+            .monster(source.1 as usize)
+            .map(|m| (m.damage(), m.school())),
+        PLAYER_HAND_ROW => match board.hand().get(source.1 as usize) {
+            Some(CardHolder::MagicCard(magic)) => {
+                magic
+                    .effects()
+                    .iter()
+                    .find_map(|e| match (e.kind(), e.target()) {
+                        (EffectKind::DealDamage(n), TargetSelector::Chosen) => {
+                            Some((*n, magic.school()))
+                        }
+                        _ => None,
+                    })
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The monster's name followed by the keywords it still has, e.g. `ORC [GUARD HASTE]`.
+fn keyword_label(monster: &Monster) -> String {
+    let name = monster.data().name().to_uppercase();
+    let keywords: Vec<&str> = monster.active_keywords().iter().map(|k| k.name()).collect();
+    if keywords.is_empty() {
+        name
+    } else {
+        format!("{} [{}]", name, keywords.join(" "))
+    }
+}
+
+/// Lists the resistances of the highlighted monster or hero, and how hard the selected
+/// attacker or spell would hit it.
+pub fn draw_matchups(ecs: &World, ctx: &mut Rltk) {
+    let boards = ecs.read_storage::<Board>();
+    let db = ecs.fetch::<CardDatabase>();
+    let matchups = db.matchups();
+    let (player, enemy) = match (
+        (&boards).join().find(|b| b.id() == 0),
+        (&boards).join().find(|b| b.id() != 0),
+    ) {
+        (Some(player), Some(enemy)) => (player, enemy),
+        _ => return,
+    };
+
+    let highlighted = player.highlighted();
+    let slot = highlighted.1 as usize;
+    let (label, affinity): (String, Box<dyn Fn(MagicSchool) -> Affinity>) = match highlighted.0 {
+        ENEMY_HAND_ROW => (
+            "ENEMY HERO".to_string(),
+            Box::new(|school| matchups.hero_affinity(school, enemy.hero())),
+        ),
+        PLAYER_HAND_ROW => (
+            "YOUR HERO".to_string(),
+            Box::new(|school| matchups.hero_affinity(school, player.hero())),
+        ),
+        ENEMY_FIELD_ROW | PLAYER_FIELD_ROW => {
+            let board = if highlighted.0 == ENEMY_FIELD_ROW {
+                enemy
+            } else {
+                player
+            };
+            match board.monster(slot) {
+                Some(monster) => (
+                    keyword_label(monster),
+                    Box::new(move |school| matchups.monster_affinity(school, monster)),
+                ),
+                None => return,
+            }
+        }
+        _ => return,
+    };
+
+    let profile: Vec<String> = MAGIC_SCHOOLS
+        .iter()
+        .map(|school| (school, affinity(*school)))
+        .filter(|(_, a)| *a != Affinity::Normal)
+        .map(|(school, a)| format!("{} {}", a.name(), school.name()))
+        .collect();
+    let mut text = if profile.is_empty() {
+        format!("{}: NO RESISTANCES", label)
+    } else {
+        format!("{}: {}", label, profile.join(", "))
+    };
+    let source = ecs.fetch::<Selection>().source;
+    if let Some((amount, school)) = source.and_then(|s| selected_hit(player, s)) {
+        let a = affinity(school);
+        text = format!(
+            "{} - HIT FOR {} ({})",
+            text,
+            matchups.scale(amount, a),
+            a.name()
+        );
+    }
+
+    ctx.print_color(
+        0,
+        HIGHLIGHTED_TEXT + 3,
+        RGB::named(rltk::CYAN),
+        RGB::named(rltk::BLACK),
+        text,
+    );
+}
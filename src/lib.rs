@@ -0,0 +1,18 @@
+//! Rules engine for the card game. Nothing in here depends on rltk, so a match can be
+//! driven entirely through function calls; the window lives in the `tcg_project` binary.
+
+pub mod actions;
+pub mod ai;
+pub mod card_data;
+pub mod combat;
+pub mod deck;
+pub mod effects;
+pub mod entities;
+pub mod match_cycle;
+pub mod match_log;
+pub mod matchups;
+pub mod mulligan;
+pub mod saveload_system;
+pub mod stack;
+pub mod status;
+pub mod triggers;
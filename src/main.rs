@@ -1,22 +1,42 @@
-use rand::prelude::*;
-use rltk::{GameState, Point, Rltk};
+use rltk::{GameState, RandomNumberGenerator, Rltk};
 use specs::prelude::*;
+use std::path::Path;
 
-use entities::{Board, CardHolder, Game, Hero, HeroClass, Monster, MonsterData};
+use deck_builder::DeckBuilder;
+use tcg_project::card_data::{self, CardDatabase};
+use tcg_project::deck::{self, DeckError, DeckList, DECK_DIR};
+use tcg_project::entities::{Board, Hero, MAX_HAND_SIZE};
+use tcg_project::mulligan::Mulligan;
+use tcg_project::{ai, match_cycle, saveload_system};
 
-mod card_data;
-mod entities;
+mod deck_builder;
 mod gui;
-mod match_cycle;
 mod player_system;
 
+/// The board played by the computer.
+const AI_PLAYER: u8 = 1;
+
 pub enum Command {
     ChooseSource(String),
     ChooseTarget(String),
 }
 
+/// Which screen the window is showing.
+#[derive(Copy, Clone, PartialEq)]
+pub enum RunState {
+    DeckBuilder,
+    Match,
+}
+
 pub struct State {
     pub ecs: World,
+    /// The seed every new match starts from, if one was given on the command line.
+    pub seed: Option<u64>,
+    /// How many cards each hand holds in new matches.
+    pub hand_size: usize,
+    /// Whether the player going second in new matches draws an extra card after the
+    /// mulligan.
+    pub compensation: bool,
 }
 
 impl State {
@@ -26,21 +46,110 @@ impl State {
 
         self.ecs.maintain();
     }
-}
 
-impl GameState for State {
-    fn tick(&mut self, ctx: &mut Rltk) {
-        ctx.cls();
+    fn deck_builder(&mut self, ctx: &mut Rltk) {
+        deck_builder::draw_deck_builder(&self.ecs, ctx);
+        if let Some(deck) = deck_builder::deck_builder_input(&mut self.ecs, ctx) {
+            match new_match(
+                &mut self.ecs,
+                &deck,
+                self.seed,
+                self.hand_size,
+                self.compensation,
+            ) {
+                Ok(()) => *self.ecs.write_resource::<RunState>() = RunState::Match,
+                Err(e) => self
+                    .ecs
+                    .write_resource::<DeckBuilder>()
+                    .set_message(e.to_string()),
+            }
+        }
+    }
 
+    fn play_match(&mut self, ctx: &mut Rltk) {
         gui::draw_empty_board(&self.ecs, ctx, 0);
         gui::draw_empty_board(&self.ecs, ctx, 1);
         gui::draw_filled_board(&self.ecs, ctx);
         gui::display_hand(&self.ecs, ctx);
+        gui::draw_selection_hint(&self.ecs, ctx);
+        gui::draw_turn(&self.ecs, ctx);
+        gui::draw_resources(&self.ecs, ctx);
+        gui::draw_match_status(&self.ecs, ctx);
+        gui::draw_matchups(&self.ecs, ctx);
+        gui::draw_stack(&self.ecs, ctx);
+        gui::draw_graveyard(&self.ecs, ctx);
+        gui::draw_seed(&self.ecs, ctx);
+        gui::draw_log(&self.ecs, ctx);
+
+        ai::mulligan(&self.ecs, AI_PLAYER);
+        match_cycle::run_turn(&mut self.ecs);
+        if match_cycle::acting_player(&self.ecs) == Some(AI_PLAYER) {
+            ai::take_turn(&mut self.ecs, AI_PLAYER);
+        }
 
         player_system::player_input(&mut self.ecs, ctx);
     }
 }
 
+impl GameState for State {
+    fn tick(&mut self, ctx: &mut Rltk) {
+        ctx.cls();
+
+        let runstate = *self.ecs.fetch::<RunState>();
+        match runstate {
+            RunState::DeckBuilder => self.deck_builder(ctx),
+            RunState::Match => self.play_match(ctx),
+        }
+    }
+}
+
+/// Deals out a fresh match between `player_deck` and a random valid deck from the
+/// deck builder for the computer, all of it drawn from `seed` or a fresh one, with
+/// hands holding up to `hand_size` cards and, with `compensation`, an extra card for
+/// the player going second.
+fn new_match(
+    ecs: &mut World,
+    player_deck: &DeckList,
+    seed: Option<u64>,
+    hand_size: usize,
+    compensation: bool,
+) -> Result<(), DeckError> {
+    let seed = seed.unwrap_or_else(|| RandomNumberGenerator::new().next_u64());
+    match_cycle::seed_match(ecs, seed);
+    let (deck1, deck2, opponent_class) = {
+        let card_db = ecs.fetch::<CardDatabase>();
+        let builder = ecs.fetch::<DeckBuilder>();
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+
+        let candidates: Vec<&DeckList> = builder
+            .decks()
+            .iter()
+            .map(|(_, deck)| deck)
+            .filter(|deck| deck.validate(&card_db).is_ok())
+            .collect();
+        let opponent_deck = rng
+            .random_slice_entry(&candidates)
+            .copied()
+            .unwrap_or(player_deck);
+
+        (
+            player_deck.build(&card_db, &mut rng)?,
+            opponent_deck.build(&card_db, &mut rng)?,
+            opponent_deck.class(),
+        )
+    };
+    let hero1 = Hero::new(0, 30, 30, player_deck.class());
+    let hero2 = Hero::new(1, 30, 30, opponent_class);
+    let mut board1 = Board::new(0, hero1, deck1);
+    let mut board2 = Board::new(1, hero2, deck2);
+    board1.set_max_hand_size(hand_size);
+    board2.set_max_hand_size(hand_size);
+    ecs.write_resource::<Mulligan>()
+        .set_compensation(compensation);
+    match_cycle::start_match(ecs, board1, board2);
+    Ok(())
+}
+
 fn main() -> rltk::BError {
     use rltk::RltkBuilder;
 
@@ -49,62 +158,55 @@ fn main() -> rltk::BError {
         .with_title("TCG PROJECT")
         .build()?;
 
-    let mut gs = State { ecs: World::new() };
-
-    gs.ecs.register::<Monster>();
-    gs.ecs.register::<Board>();
-    gs.ecs.register::<Hero>();
-    // gs.ecs.register::<SimpleMarker<SerializeMe>>();
-    // gs.ecs.register::<SerializationHelper>();
-
-    card_data::initialize_card_data(&mut gs.ecs);
-    let hero1 = Hero::new(0, 30, 30, HeroClass::Mage);
-    let hero2 = Hero::new(0, 30, 30, HeroClass::Mage);
-    let mut deck1 = vec![];
-    let mut deck2 = vec![];
-    {
-        let monster_data = gs.ecs.entry::<Vec<MonsterData>>().or_insert(vec![]);
-
-        // gs.ecs.insert(SimpleMarkerAllocator::<SerializeMe>::new());
-        let mut id = 0;
-        {
-            for _ in 0..30 {
-                let orc1 = CardHolder::MonsterCard(Monster::new(id, (&monster_data[0]).clone()));
-                let orc2 = CardHolder::MonsterCard(Monster::new(id, (&monster_data[0]).clone()));
-                let wisp1 =
-                    CardHolder::MonsterCard(Monster::new(id + 30, (&monster_data[1]).clone()));
-                let wisp2 =
-                    CardHolder::MonsterCard(Monster::new(id + 30, (&monster_data[1]).clone()));
-
-                let mut rng = rand::thread_rng();
-
-                let n1: u8 = rng.gen();
-                let n2: u8 = rng.gen();
-
-                if n1 % 2 == 0 {
-                    deck1.push(orc1);
-                } else {
-                    deck1.push(wisp1);
-                }
-                if n2 % 2 == 0 {
-                    deck2.push(orc2);
-                } else {
-                    deck2.push(wisp2);
-                }
-
-                id += 1;
-            }
+    let mut args = std::env::args().skip(1);
+    let mut save = None;
+    let mut seed = None;
+    let mut hand_size = MAX_HAND_SIZE;
+    let mut compensation = true;
+    while let Some(arg) = args.next() {
+        if arg == "--seed" {
+            let value = args.next().unwrap_or_default();
+            seed = Some(
+                value
+                    .parse::<u64>()
+                    .map_err(|_| format!("--seed needs a whole number, not \"{}\"", value))?,
+            );
+        } else if arg == "--hand-size" {
+            let value = args.next().unwrap_or_default();
+            hand_size = value
+                .parse::<usize>()
+                .map_err(|_| format!("--hand-size needs a whole number, not \"{}\"", value))?;
+        } else if arg == "--no-compensation" {
+            compensation = false;
+        } else {
+            save = Some(arg);
         }
     }
-    let board1 = Board::new(0, hero1, deck1);
-    let board2 = Board::new(1, hero2, deck2);
-    {
-        gs.ecs.create_entity().with(board1).build();
-    }
-    {
-        gs.ecs.create_entity().with(board2).build();
-    }
-    match_cycle::pre_game(&gs.ecs);
+
+    let mut gs = State {
+        ecs: match_cycle::new_world(),
+        seed,
+        hand_size,
+        compensation,
+    };
+    gs.ecs.insert(player_system::Selection::default());
+
+    card_data::initialize_card_data(&mut gs.ecs)?;
+    let decks = deck::load_decks(Path::new(DECK_DIR))?;
+    gs.ecs.insert(DeckBuilder::new(decks));
+
+    // A save file given on the command line is resumed instead of building a deck first.
+    // `--seed <n>` makes every new match play out the same for the same moves, and
+    // `--hand-size <n>` changes how many cards a hand holds before draws burn.
+    // `--no-compensation` drops the extra card for the player going second.
+    let runstate = match save {
+        Some(path) => {
+            saveload_system::load_game(&mut gs.ecs, Path::new(&path))?;
+            RunState::Match
+        }
+        None => RunState::DeckBuilder,
+    };
+    gs.ecs.insert(runstate);
     // gs.ecs.insert(Game::new(board1, board2));
 
     // let map: Map = Map::new_map_rooms_and_corridors();
@@ -112,7 +214,6 @@ fn main() -> rltk::BError {
 
     // let player_entity = spawner::player(&mut gs.ecs, player_x, player_y);
 
-    // gs.ecs.insert(rltk::RandomNumberGenerator::new());
     // for room in map.rooms.iter().skip(1) {
     //     spawner::spawn_room(&mut gs.ecs, room);
     // }
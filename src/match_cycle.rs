@@ -1,5 +1,61 @@
-use super::entities::{Board, DamageType, MagicSchool};
+use super::actions::ActionError;
+use super::card_data::CardDatabase;
+use super::entities::{
+    Board, Hero, MatchSeed, MatchStatus, Monster, SerializationHelper, SerializeMe, Turn, TurnPhase,
+};
+use super::match_log::{self, MatchLog};
+use super::mulligan::{self, Mulligan};
+use super::stack::EffectStack;
+use super::status;
+use super::triggers;
+use bracket_random::prelude::RandomNumberGenerator;
 use specs::prelude::*;
+use specs::saveload::{MarkedBuilder, SimpleMarker, SimpleMarkerAllocator};
+
+/// A world with every component and resource a match needs, its random number
+/// generator started from a fresh seed. Card data still has to be loaded into it, see
+/// `card_data::initialize_card_data`.
+pub fn new_world() -> World {
+    let mut ecs = World::new();
+
+    ecs.register::<Monster>();
+    ecs.register::<Board>();
+    ecs.register::<Hero>();
+    ecs.register::<SimpleMarker<SerializeMe>>();
+    ecs.register::<SerializationHelper>();
+
+    ecs.insert(SimpleMarkerAllocator::<SerializeMe>::new());
+    ecs.insert(Turn::new(0));
+    ecs.insert(MatchStatus::Ongoing);
+    ecs.insert(EffectStack::default());
+    ecs.insert(Mulligan::default());
+    ecs.insert(MatchLog::default());
+    seed_match(&mut ecs, RandomNumberGenerator::new().next_u64());
+
+    ecs
+}
+
+/// Restarts the world's random number generator from `seed` and records the seed with
+/// the match. Everything random in a match draws from that one generator, starting
+/// with shuffling the decks, so call this before building them.
+pub fn seed_match(ecs: &mut World, seed: u64) {
+    ecs.insert(RandomNumberGenerator::seeded(seed));
+    ecs.insert(MatchSeed(seed));
+}
+
+/// Puts both boards into the world and deals the opening hands. The first turn waits
+/// until both players are through the mulligan, see `mulligan::replace`.
+pub fn start_match(ecs: &mut World, board1: Board, board2: Board) {
+    for board in [board1, board2] {
+        ecs.create_entity()
+            .with(board)
+            .marked::<SimpleMarker<SerializeMe>>()
+            .build();
+    }
+    pre_game(ecs);
+    match_log::collect(ecs);
+    mulligan::begin(ecs);
+}
 
 pub fn pre_game(ecs: &World) {
     let entities = ecs.entities();
@@ -15,10 +71,187 @@ pub fn pre_game(ecs: &World) {
     }
 }
 
-pub fn pre_play(player: &mut Board) {
+pub fn pre_play(
+    player: &mut Board,
+    opponent: &mut Board,
+    db: &CardDatabase,
+    rng: &mut RandomNumberGenerator,
+) {
+    player.resources_mut().grow_and_refill();
+    player.hero_mut().ready();
+    status::start_of_turn(player);
+    triggers::resolve_pending(player, opponent, db, rng);
+    triggers::phase(TurnPhase::PrePlay, player, opponent, db, rng);
+    player.ready_monsters();
     player.draw_card();
 }
 
-pub fn in_play(player: &mut Board) {}
+pub fn in_play(player: &mut Board, opponent: &mut Board) {
+    player.remove_dead_monsters();
+    opponent.remove_dead_monsters();
+}
+
+pub fn post_play(
+    player: &mut Board,
+    opponent: &mut Board,
+    db: &CardDatabase,
+    rng: &mut RandomNumberGenerator,
+) {
+    triggers::phase(TurnPhase::PostPlay, player, opponent, db, rng);
+    player.age_lingering();
+    status::end_of_turn(player);
+    player.end_turn_modifiers();
+    opponent.end_turn_modifiers();
+}
+
+/// Works out whether a hero has died. Both heroes dying at once is a draw.
+pub fn check_match_status(ecs: &World) -> MatchStatus {
+    let boards = ecs.read_storage::<Board>();
+    let dead: Vec<u8> = (&boards)
+        .join()
+        .filter(|b| b.hero().is_dead())
+        .map(|b| b.id())
+        .collect();
+    match dead.as_slice() {
+        [] => MatchStatus::Ongoing,
+        [loser] => MatchStatus::Won(1 - loser),
+        _ => MatchStatus::Drawn,
+    }
+}
+
+/// Stores the outcome of `check_match_status` once a hero has died. Called after
+/// everything that can deal damage, so no one acts on in a match that is over.
+pub fn update_match_status(ecs: &World) {
+    let status = check_match_status(ecs);
+    if status.is_over() {
+        *ecs.write_resource::<MatchStatus>() = status;
+    }
+}
+
+/// Steps the turn state machine stored in the `Turn` resource. PrePlay and PostPlay
+/// run once and move on by themselves; InPlay lasts until `end_turn` is called.
+/// Nothing happens any more once the match is over.
+pub fn run_turn(ecs: &mut World) {
+    if ecs.fetch::<MatchStatus>().is_over() || ecs.fetch::<Mulligan>().is_open() {
+        return;
+    }
+    update_match_status(ecs);
+    if ecs.fetch::<MatchStatus>().is_over() {
+        return;
+    }
+
+    let mut turn = *ecs.fetch::<Turn>();
+    {
+        let db = ecs.fetch::<CardDatabase>();
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        let mut boards = ecs.write_storage::<Board>();
+        let (player, opponent) = match split_boards(&mut boards, turn.player()) {
+            Some(boards) => boards,
+            None => return,
+        };
+
+        match turn.phase() {
+            TurnPhase::PrePlay => {
+                pre_play(player, opponent, &db, &mut rng);
+                turn.set_phase(TurnPhase::InPlay);
+            }
+            TurnPhase::InPlay => in_play(player, opponent),
+            TurnPhase::PostPlay => {
+                post_play(player, opponent, &db, &mut rng);
+                turn = turn.next();
+            }
+        }
+    }
+    // Logged before the turn moves on, so end of turn events keep their turn number.
+    match_log::collect(ecs);
+    *ecs.write_resource::<Turn>() = turn;
+    update_match_status(ecs);
+}
+
+/// Runs the automatic phases until a player has to act or the match is over.
+pub fn advance(ecs: &mut World) {
+    update_match_status(ecs);
+    loop {
+        let before = *ecs.fetch::<Turn>();
+        if ecs.fetch::<MatchStatus>().is_over() || before.phase() == TurnPhase::InPlay {
+            return;
+        }
+        run_turn(ecs);
+        if *ecs.fetch::<Turn>() == before && !ecs.fetch::<MatchStatus>().is_over() {
+            return;
+        }
+    }
+}
+
+/// The player who still owes discards, the one whose turn it is first.
+pub fn discarding_player(ecs: &World) -> Option<u8> {
+    let active = ecs.fetch::<Turn>().player();
+    let boards = ecs.read_storage::<Board>();
+    let mut owing: Vec<u8> = (&boards)
+        .join()
+        .filter(|b| b.discards_pending() > 0)
+        .map(|b| b.id())
+        .collect();
+    owing.sort_by_key(|id| *id != active);
+    owing.first().copied()
+}
 
-pub fn post_play() {}
+/// The player who may take actions right now: whoever owes discards, then whoever
+/// holds priority while the stack has cards on it, otherwise the player whose InPlay
+/// phase it is.
+pub fn acting_player(ecs: &World) -> Option<u8> {
+    let turn = ecs.fetch::<Turn>();
+    if ecs.fetch::<MatchStatus>().is_over()
+        || ecs.fetch::<Mulligan>().is_open()
+        || turn.phase() != TurnPhase::InPlay
+    {
+        return None;
+    }
+    discarding_player(ecs)
+        .or(ecs.fetch::<EffectStack>().priority())
+        .or(Some(turn.player()))
+}
+
+/// Checks that `player` may take actions right now.
+pub fn check_can_act(ecs: &World, player: u8) -> Result<(), ActionError> {
+    if ecs.fetch::<MatchStatus>().is_over() {
+        Err(ActionError::MatchOver)
+    } else if acting_player(ecs) != Some(player) {
+        Err(ActionError::NotYourTurn)
+    } else {
+        Ok(())
+    }
+}
+
+/// Whether `player` may take actions right now.
+pub fn can_act(ecs: &World, player: u8) -> bool {
+    check_can_act(ecs, player).is_ok()
+}
+
+/// Ends the InPlay phase of `player`'s turn. Not allowed while cards still wait on
+/// the stack.
+pub fn end_turn(ecs: &mut World, player: u8) -> Result<(), ActionError> {
+    check_can_act(ecs, player)?;
+    if !ecs.fetch::<EffectStack>().is_empty() {
+        return Err(ActionError::StackNotEmpty);
+    }
+    ecs.write_resource::<Turn>().set_phase(TurnPhase::PostPlay);
+    Ok(())
+}
+
+/// Borrows the board of `player` together with the opposing board.
+pub fn split_boards<'a>(
+    boards: &'a mut WriteStorage<Board>,
+    player: u8,
+) -> Option<(&'a mut Board, &'a mut Board)> {
+    let mut own = None;
+    let mut opponent = None;
+    for board in (boards).join() {
+        if board.id() == player {
+            own = Some(board);
+        } else {
+            opponent = Some(board);
+        }
+    }
+    own.zip(opponent)
+}
@@ -0,0 +1,55 @@
+use super::entities::{Board, LogEvent, Turn};
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+
+/// One line of the match log: what happened to whose cards, and in which turn.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    turn: u32,
+    player: u8,
+    event: LogEvent,
+}
+
+impl LogEntry {
+    pub fn turn(&self) -> u32 {
+        self.turn
+    }
+
+    /// The player whose cards the event happened to.
+    pub fn player(&self) -> u8 {
+        self.player
+    }
+
+    pub fn event(&self) -> &LogEvent {
+        &self.event
+    }
+}
+
+/// Everything both players were told about during the match, oldest first.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct MatchLog {
+    entries: Vec<LogEntry>,
+}
+
+impl MatchLog {
+    pub fn entries(&self) -> &[LogEntry] {
+        &self.entries
+    }
+}
+
+/// Moves the events queued on the boards into the log, board by board, under the
+/// current turn.
+pub fn collect(ecs: &World) {
+    let turn = ecs.fetch::<Turn>().number();
+    let mut log = ecs.write_resource::<MatchLog>();
+    let mut boards = ecs.write_storage::<Board>();
+    for board in (&mut boards).join() {
+        let player = board.id();
+        log.entries
+            .extend(board.take_logged().into_iter().map(|event| LogEntry {
+                turn,
+                player,
+                event,
+            }));
+    }
+}
@@ -0,0 +1,81 @@
+use super::entities::{Affinity, DefenseProfile, Hero, HeroClass, MagicSchool, Monster};
+use serde::{Deserialize, Serialize};
+
+/// Turns a school hitting a monster or hero into final damage. What is printed on a
+/// monster card wins over the school defaults listed here.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MatchupTable {
+    /// Percent of the damage that goes through against a resistant target.
+    resistant: i32,
+    /// Percent of the damage that goes through against a weak target.
+    weak: i32,
+    /// Percent of the damage that goes through against an immune target.
+    immune: i32,
+    /// How monsters of the second school fare against attacks of the first.
+    #[serde(default)]
+    schools: Vec<(MagicSchool, MagicSchool, Affinity)>,
+    /// The defense profile of each hero class.
+    #[serde(default)]
+    classes: Vec<(HeroClass, DefenseProfile)>,
+}
+
+impl Default for MatchupTable {
+    fn default() -> MatchupTable {
+        MatchupTable {
+            resistant: 50,
+            weak: 200,
+            immune: 0,
+            schools: vec![],
+            classes: vec![],
+        }
+    }
+}
+
+impl MatchupTable {
+    pub fn percent(&self, affinity: Affinity) -> i32 {
+        match affinity {
+            Affinity::Normal => 100,
+            Affinity::Resistant => self.resistant,
+            Affinity::Weak => self.weak,
+            Affinity::Immune => self.immune,
+        }
+    }
+
+    /// Whether every percentage is usable, i.e. not negative.
+    pub fn is_valid(&self) -> bool {
+        self.resistant >= 0 && self.weak >= 0 && self.immune >= 0
+    }
+
+    pub fn monster_affinity(&self, school: MagicSchool, monster: &Monster) -> Affinity {
+        monster
+            .defense()
+            .affinity(school)
+            .or_else(|| {
+                self.schools
+                    .iter()
+                    .find(|(attack, defense, _)| *attack == school && *defense == monster.school())
+                    .map(|(_, _, affinity)| *affinity)
+            })
+            .unwrap_or(Affinity::Normal)
+    }
+
+    pub fn hero_affinity(&self, school: MagicSchool, hero: &Hero) -> Affinity {
+        self.classes
+            .iter()
+            .find(|(class, _)| *class == hero.class())
+            .and_then(|(_, profile)| profile.affinity(school))
+            .unwrap_or(Affinity::Normal)
+    }
+
+    pub fn scale(&self, amount: i32, affinity: Affinity) -> i32 {
+        amount * self.percent(affinity) / 100
+    }
+
+    pub fn damage_to_monster(&self, amount: i32, school: MagicSchool, monster: &Monster) -> i32 {
+        self.scale(amount, self.monster_affinity(school, monster))
+    }
+
+    pub fn damage_to_hero(&self, amount: i32, school: MagicSchool, hero: &Hero) -> i32 {
+        self.scale(amount, self.hero_affinity(school, hero))
+    }
+}
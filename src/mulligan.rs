@@ -0,0 +1,111 @@
+use super::actions::ActionError;
+use super::entities::{Board, CardHolder, Turn};
+use super::match_cycle;
+use super::match_log;
+use bracket_random::prelude::RandomNumberGenerator;
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+
+/// The players still deciding which cards of their opening hand to shuffle back.
+/// The first turn only starts once everyone has.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Mulligan {
+    waiting: Vec<u8>,
+    /// Whether the player going second draws an extra card once everyone is done.
+    compensation: bool,
+}
+
+impl Default for Mulligan {
+    fn default() -> Mulligan {
+        Mulligan {
+            waiting: vec![],
+            compensation: true,
+        }
+    }
+}
+
+impl Mulligan {
+    pub fn is_waiting(&self, player: u8) -> bool {
+        self.waiting.contains(&player)
+    }
+
+    /// Whether anyone still has to decide on their opening hand.
+    pub fn is_open(&self) -> bool {
+        !self.waiting.is_empty()
+    }
+
+    pub fn compensation(&self) -> bool {
+        self.compensation
+    }
+
+    pub fn set_compensation(&mut self, compensation: bool) {
+        self.compensation = compensation;
+    }
+}
+
+/// Lets every player in the match decide on their opening hand.
+pub fn begin(ecs: &World) {
+    let players = (&ecs.read_storage::<Board>())
+        .join()
+        .map(|b| b.id())
+        .collect();
+    ecs.write_resource::<Mulligan>().waiting = players;
+}
+
+/// Draws as many new cards as there are `cards` in `player`'s opening hand, then
+/// shuffles those cards back into the deck. An empty list keeps the hand as it is.
+/// Once the last player is done, the one going second draws an extra card if the
+/// mulligan gives compensation.
+pub fn replace(ecs: &World, player: u8, cards: &[usize]) -> Result<(), ActionError> {
+    let result = replace_cards(ecs, player, cards);
+    match_log::collect(ecs);
+    match_cycle::update_match_status(ecs);
+    result
+}
+
+fn replace_cards(ecs: &World, player: u8, cards: &[usize]) -> Result<(), ActionError> {
+    let mut mulligan = ecs.write_resource::<Mulligan>();
+    if !mulligan.is_waiting(player) {
+        return Err(ActionError::HandKept);
+    }
+    let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+    let mut boards = ecs.write_storage::<Board>();
+    let board = (&mut boards)
+        .join()
+        .find(|b| b.id() == player)
+        .ok_or(ActionError::UnknownPlayer(player))?;
+
+    let mut cards = cards.to_vec();
+    cards.sort_unstable();
+    cards.dedup();
+    if let Some(card) = cards.iter().find(|c| **c >= board.hand().len()) {
+        return Err(ActionError::CardNotInHand(*card));
+    }
+    // Back to front, so the cards still to go keep their index.
+    let returned: Vec<CardHolder> = cards
+        .iter()
+        .rev()
+        .filter_map(|card| board.take_from_hand(*card))
+        .collect();
+    for _ in 0..returned.len() {
+        board.draw_card();
+    }
+    for card in returned {
+        board.shuffle_into_deck(card, &mut rng);
+    }
+
+    mulligan.waiting.retain(|p| *p != player);
+    if !mulligan.is_open() && mulligan.compensation {
+        let first = ecs.fetch::<Turn>().player();
+        for board in (&mut boards).join().filter(|b| b.id() != first) {
+            board.draw_card();
+        }
+    }
+    Ok(())
+}
+
+/// Keeps `player`'s opening hand as it was dealt.
+pub fn keep(ecs: &World, player: u8) -> Result<(), ActionError> {
+    replace(ecs, player, &[])
+}
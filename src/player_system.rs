@@ -2,22 +2,53 @@ use std::cmp::{max, min};
 
 use rltk::{Rltk, VirtualKeyCode};
 use specs::prelude::*;
-use super::entities::Board;
+use std::path::Path;
+use tcg_project::actions::{self, Action, ActionError};
+use tcg_project::card_data::CardDatabase;
+use tcg_project::entities::{Board, CardHolder, Target, TargetType};
+use tcg_project::match_cycle;
+use tcg_project::mulligan::{self, Mulligan};
+use tcg_project::saveload_system::{self, SAVE_FILE};
+
+pub const ENEMY_HAND_ROW: u8 = 0;
+pub const ENEMY_FIELD_ROW: u8 = 1;
+pub const PLAYER_FIELD_ROW: u8 = 2;
+pub const PLAYER_HAND_ROW: u8 = 3;
+/// Stands for the hero power when it is the source of a selection. It can't be
+/// highlighted like the rows above.
+pub const HERO_POWER_ROW: u8 = 4;
+/// Stands for the hero attacking with its weapon, like `HERO_POWER_ROW`.
+pub const HERO_WEAPON_ROW: u8 = 5;
+
+/// The highlighted position the player chose as the source of their next action,
+/// why their last action was turned down, if it was, why the last save or load
+/// failed, whose graveyard is open, and the hand cards marked to go back in the
+/// mulligan.
+#[derive(Default)]
+pub struct Selection {
+    pub source: Option<(u8, i32)>,
+    pub error: Option<ActionError>,
+    pub file_error: Option<String>,
+    pub browsing: Option<u8>,
+    pub marked: Vec<usize>,
+}
+
+/// Opens the player's graveyard, then the enemy's, then closes it again.
+fn cycle_graveyard(ecs: &mut World) {
+    let mut selection = ecs.write_resource::<Selection>();
+    selection.browsing = match selection.browsing {
+        None => Some(0),
+        Some(0) => Some(1),
+        Some(_) => None,
+    };
+}
 
 pub fn change_highlight(y: i32, x: i32, ecs: &mut World) {
-    let entities = ecs.entities();
     let mut boards = ecs.write_storage::<Board>();
-    let mut joined_storage = (&entities, &mut boards).join();
-    let (_, mut player_board) = joined_storage.nth(0).expect("No player");
-    let (_, mut enemy_board) = joined_storage.nth(0).expect("No enemy");
-
-    for (_, b) in joined_storage {
-        if b.id() == 0 {
-            player_board = b;
-        } else if b.id() == 1 {
-            enemy_board = b;
-        }
-    }
+    let (player_board, enemy_board) = match match_cycle::split_boards(&mut boards, 0) {
+        Some(boards) => boards,
+        None => return,
+    };
 
     
     let old_highlighted = player_board.highlighted();
@@ -35,11 +66,173 @@ pub fn change_highlight(y: i32, x: i32, ecs: &mut World) {
     player_board.change_highlighted(new_highlighted);
 }
 
-pub fn select_highligted(ecs: &World) {}
+/// Uses the hero power right away if it takes no target, or else waits for one.
+fn select_hero_power(ecs: &mut World) {
+    let needs_target = {
+        let boards = ecs.read_storage::<Board>();
+        let db = ecs.fetch::<CardDatabase>();
+        let power = (&boards)
+            .join()
+            .find(|b| b.id() == 0)
+            .and_then(|b| db.hero_power(b.hero().class()));
+        match power {
+            Some(power) => power.needs_target(),
+            None => return,
+        }
+    };
+    if needs_target {
+        ecs.write_resource::<Selection>().source = Some((HERO_POWER_ROW, 0));
+    } else {
+        ecs.write_resource::<Selection>().source = None;
+        perform(ecs, Action::HeroPower { target: None });
+    }
+}
+
+fn player_highlighted(ecs: &World) -> Option<(u8, i32)> {
+    let boards = ecs.read_storage::<Board>();
+    (&boards)
+        .join()
+        .find(|b| b.id() == 0)
+        .map(|b| b.highlighted())
+}
+
+/// The target picked by highlighting `position`. The hand rows stand in for the heroes.
+fn target_at(position: (u8, i32)) -> Option<Target> {
+    let slot = position.1 as usize;
+    match position.0 {
+        ENEMY_HAND_ROW => Some(Target::Enemy(TargetType::Hero)),
+        ENEMY_FIELD_ROW => Some(Target::Enemy(TargetType::Monster(slot))),
+        PLAYER_FIELD_ROW => Some(Target::Friendly(TargetType::Monster(slot))),
+        PLAYER_HAND_ROW => Some(Target::Friendly(TargetType::Hero)),
+        _ => None,
+    }
+}
+
+/// Whether the highlighted hand card is a weapon or a magic card that is played
+/// without a target.
+fn is_untargeted_card(ecs: &World, position: (u8, i32)) -> bool {
+    let boards = ecs.read_storage::<Board>();
+    match (&boards).join().find(|b| b.id() == 0) {
+        Some(board) if position.0 == PLAYER_HAND_ROW => match board.hand().get(position.1 as usize)
+        {
+            Some(CardHolder::MagicCard(magic)) => !magic.needs_target(),
+            Some(CardHolder::WeaponCard(_)) => true,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+pub fn select_highlighted(ecs: &mut World) {
+    let highlighted = match player_highlighted(ecs) {
+        Some(h) => h,
+        None => return,
+    };
+    let source = ecs.fetch::<Selection>().source;
+
+    match source {
+        None => {
+            if is_untargeted_card(ecs, highlighted) {
+                perform_action(ecs, highlighted, None);
+            } else if highlighted.0 == PLAYER_FIELD_ROW || highlighted.0 == PLAYER_HAND_ROW {
+                ecs.write_resource::<Selection>().source = Some(highlighted);
+            }
+        }
+        Some(source) => {
+            perform_action(ecs, source, target_at(highlighted));
+            ecs.write_resource::<Selection>().source = None;
+        }
+    }
+}
+
+/// Runs the action implied by picking `source` and then `target`.
+fn perform_action(ecs: &mut World, source: (u8, i32), target: Option<Target>) {
+    let source_index = source.1 as usize;
+
+    let action = match (source.0, target) {
+        (PLAYER_FIELD_ROW, Some(Target::Enemy(target_type))) => Action::Attack {
+            slot: source_index,
+            target: target_type,
+        },
+        // Picking the monster's own slot again just drops the selection.
+        (PLAYER_FIELD_ROW, Some(Target::Friendly(TargetType::Monster(to))))
+            if to != source_index =>
+        {
+            Action::Move {
+                slot: source_index,
+                to,
+            }
+        }
+        (PLAYER_HAND_ROW, target) => Action::PlayCard {
+            card: source_index,
+            target,
+        },
+        (HERO_POWER_ROW, target) => Action::HeroPower { target },
+        (HERO_WEAPON_ROW, Some(Target::Enemy(target))) => Action::HeroAttack { target },
+        _ => return,
+    };
+    perform(ecs, action);
+}
+
+/// Runs `action` for the player and keeps the reason if it was turned down.
+fn perform(ecs: &mut World, action: Action) {
+    let result = actions::perform(ecs, 0, action);
+    ecs.write_resource::<Selection>().error = result.err();
+}
+
+/// While the player's opening hand is open, Return marks or unmarks the highlighted
+/// hand card and E shuffles the marked ones back. Returns whether it used `key`.
+fn mulligan_input(ecs: &mut World, key: VirtualKeyCode) -> bool {
+    if !ecs.fetch::<Mulligan>().is_waiting(0) {
+        return false;
+    }
+    match key {
+        VirtualKeyCode::Return => {
+            if let Some((PLAYER_HAND_ROW, card)) = player_highlighted(ecs) {
+                let card = card as usize;
+                let mut selection = ecs.write_resource::<Selection>();
+                match selection.marked.iter().position(|c| *c == card) {
+                    Some(index) => {
+                        selection.marked.remove(index);
+                    }
+                    None => selection.marked.push(card),
+                }
+            }
+            true
+        }
+        VirtualKeyCode::E => {
+            let marked = std::mem::take(&mut ecs.write_resource::<Selection>().marked);
+            let result = mulligan::replace(ecs, 0, &marked);
+            ecs.write_resource::<Selection>().error = result.err();
+            true
+        }
+        _ => false,
+    }
+}
+
+/// While the player owes discards, Return discards the highlighted hand card.
+/// Returns whether it used `key`.
+fn discard_input(ecs: &mut World, key: VirtualKeyCode) -> bool {
+    if key != VirtualKeyCode::Return || match_cycle::discarding_player(ecs) != Some(0) {
+        return false;
+    }
+    if let Some((PLAYER_HAND_ROW, card)) = player_highlighted(ecs) {
+        ecs.write_resource::<Selection>().source = None;
+        perform(
+            ecs,
+            Action::Discard {
+                card: card as usize,
+            },
+        );
+    }
+    true
+}
 
 pub fn player_input(ecs: &mut World, ctx: &mut Rltk) {
     match ctx.key {
         None => return, // Nothing happened
+        Some(key) if mulligan_input(ecs, key) => {}
+        Some(key) if discard_input(ecs, key) => {}
         Some(key) => match key {
             VirtualKeyCode::Left => change_highlight(0, -1, ecs),
 
@@ -49,7 +242,58 @@ pub fn player_input(ecs: &mut World, ctx: &mut Rltk) {
 
             VirtualKeyCode::Down => change_highlight(1, 0, ecs),
 
-            VirtualKeyCode::Return => select_highligted(ecs),
+            VirtualKeyCode::Return => {
+                if match_cycle::can_act(ecs, 0) {
+                    select_highlighted(ecs)
+                }
+            }
+
+            VirtualKeyCode::S => {
+                let result = saveload_system::save_game(ecs, Path::new(SAVE_FILE));
+                ecs.write_resource::<Selection>().file_error = result
+                    .err()
+                    .map(|e| format!("Could not save to {}: {}", SAVE_FILE, e));
+            }
+
+            VirtualKeyCode::L => {
+                let result = saveload_system::load_game(ecs, Path::new(SAVE_FILE));
+                let mut selection = ecs.write_resource::<Selection>();
+                match result {
+                    Ok(()) => {
+                        selection.source = None;
+                        selection.file_error = None;
+                    }
+                    Err(e) => {
+                        selection.file_error = Some(format!("Could not load {}: {}", SAVE_FILE, e))
+                    }
+                }
+            }
+
+            VirtualKeyCode::E => {
+                ecs.write_resource::<Selection>().source = None;
+                perform(ecs, Action::EndTurn);
+            }
+
+            VirtualKeyCode::P => {
+                ecs.write_resource::<Selection>().source = None;
+                perform(ecs, Action::Pass);
+            }
+
+            VirtualKeyCode::G => cycle_graveyard(ecs),
+
+            VirtualKeyCode::W => {
+                if match_cycle::can_act(ecs, 0) {
+                    ecs.write_resource::<Selection>().source = Some((HERO_WEAPON_ROW, 0));
+                }
+            }
+
+            VirtualKeyCode::H => {
+                if match_cycle::can_act(ecs, 0) {
+                    select_hero_power(ecs)
+                }
+            }
+
+            VirtualKeyCode::Escape => *ecs.write_resource::<Selection>() = Selection::default(),
 
             _ => return,
         },
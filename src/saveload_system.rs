@@ -0,0 +1,150 @@
+use super::entities::{Board, MatchSeed, MatchStatus, SerializationHelper, SerializeMe, Turn};
+use super::match_log::MatchLog;
+use super::mulligan::Mulligan;
+use super::stack::EffectStack;
+use bracket_random::prelude::RandomNumberGenerator;
+use specs::error::NoError;
+use specs::prelude::*;
+use specs::saveload::{
+    DeserializeComponents, MarkedBuilder, SerializeComponents, SimpleMarker, SimpleMarkerAllocator,
+};
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+pub const SAVE_FILE: &str = "savegame.json";
+
+macro_rules! serialize_individually {
+    ($ecs:expr, $ser:expr, $data:expr, $( $type:ty),*) => {
+        $(
+        SerializeComponents::<NoError, SimpleMarker<SerializeMe>>::serialize(
+            &( $ecs.read_storage::<$type>(), ),
+            &$data.0,
+            &$data.1,
+            &mut $ser,
+        )
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+        )*
+    };
+}
+
+macro_rules! deserialize_individually {
+    ($ecs:expr, $de:expr, $data:expr, $( $type:ty),*) => {
+        $(
+        DeserializeComponents::<NoError, _>::deserialize(
+            &mut ( &mut $ecs.write_storage::<$type>(), ),
+            &$data.0,
+            &mut $data.1,
+            &mut $data.2,
+            &mut $de,
+        )
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        )*
+    };
+}
+
+/// Writes both boards, the turn, the match status, the stack, the mulligan, the match
+/// log, the seed and the RNG state to `path`.
+pub fn save_game(ecs: &mut World, path: &Path) -> io::Result<()> {
+    let helper = SerializationHelper {
+        turn: *ecs.fetch::<Turn>(),
+        status: *ecs.fetch::<MatchStatus>(),
+        rng: (*ecs.fetch::<RandomNumberGenerator>()).clone(),
+        seed: *ecs.fetch::<MatchSeed>(),
+        stack: (*ecs.fetch::<EffectStack>()).clone(),
+        mulligan: (*ecs.fetch::<Mulligan>()).clone(),
+        log: (*ecs.fetch::<MatchLog>()).clone(),
+    };
+    let save_helper = ecs
+        .create_entity()
+        .with(helper)
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+
+    let result = (|| {
+        let data = (
+            ecs.entities(),
+            ecs.read_storage::<SimpleMarker<SerializeMe>>(),
+        );
+        let writer = File::create(path)?;
+        let mut serializer = serde_json::Serializer::new(writer);
+        serialize_individually!(ecs, serializer, data, Board, SerializationHelper);
+        Ok(())
+    })();
+
+    ecs.delete_entity(save_helper)
+        .expect("Save helper entity was already gone");
+    ecs.maintain();
+    result
+}
+
+/// Reads the boards and match state saved in `data` into a world of their own.
+fn read_save(data: &str) -> io::Result<(Vec<Board>, SerializationHelper)> {
+    let mut scratch = World::new();
+    scratch.register::<Board>();
+    scratch.register::<SimpleMarker<SerializeMe>>();
+    scratch.register::<SerializationHelper>();
+    scratch.insert(SimpleMarkerAllocator::<SerializeMe>::new());
+
+    {
+        let mut de = serde_json::Deserializer::from_str(data);
+        let mut d = (
+            &mut scratch.entities(),
+            &mut scratch.write_storage::<SimpleMarker<SerializeMe>>(),
+            &mut scratch.write_resource::<SimpleMarkerAllocator<SerializeMe>>(),
+        );
+        deserialize_individually!(scratch, de, d, Board, SerializationHelper);
+    }
+
+    let boards: Vec<Board> = (&scratch.read_storage::<Board>()).join().cloned().collect();
+    if boards.len() != 2 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Save file doesn't have both boards",
+        ));
+    }
+    let helper = (&scratch.read_storage::<SerializationHelper>())
+        .join()
+        .next()
+        .cloned()
+        .ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, "Save file has no match state")
+        })?;
+    Ok((boards, helper))
+}
+
+/// Replaces the current match with the one saved in `path`. The save is read in full
+/// first, so one that can't be read leaves the current match as it was.
+pub fn load_game(ecs: &mut World, path: &Path) -> io::Result<()> {
+    let data = std::fs::read_to_string(path)?;
+    let (boards, helper) = read_save(&data)?;
+
+    {
+        let mut to_delete = vec![];
+        for entity in ecs.entities().join() {
+            to_delete.push(entity);
+        }
+        for entity in to_delete {
+            ecs.delete_entity(entity)
+                .expect("Deleting an entity that was just listed");
+        }
+    }
+    ecs.maintain();
+
+    for board in boards {
+        ecs.create_entity()
+            .with(board)
+            .marked::<SimpleMarker<SerializeMe>>()
+            .build();
+    }
+
+    *ecs.write_resource::<Turn>() = helper.turn;
+    *ecs.write_resource::<MatchStatus>() = helper.status;
+    *ecs.write_resource::<RandomNumberGenerator>() = helper.rng;
+    *ecs.write_resource::<MatchSeed>() = helper.seed;
+    *ecs.write_resource::<EffectStack>() = helper.stack;
+    *ecs.write_resource::<Mulligan>() = helper.mulligan;
+    *ecs.write_resource::<MatchLog>() = helper.log;
+
+    Ok(())
+}
@@ -0,0 +1,211 @@
+use super::actions::ActionError;
+use super::card_data::CardDatabase;
+use super::effects;
+use super::entities::{
+    Board, Card, CardHolder, EffectKind, Magic, Played, Target, TargetSelector, TargetType, Turn,
+};
+use super::match_cycle;
+use super::triggers;
+use bracket_random::prelude::RandomNumberGenerator;
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+
+/// A magic card that has been paid for and waits on the stack to resolve.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct StackEntry {
+    controller: u8,
+    spell: Magic,
+    target: Option<Target>,
+    /// The monster `target` pointed at when the card was played, so one that moves
+    /// into the slot later isn't hit in its place.
+    target_card: Option<u32>,
+}
+
+impl StackEntry {
+    pub fn controller(&self) -> u8 {
+        self.controller
+    }
+
+    pub fn spell(&self) -> &Magic {
+        &self.spell
+    }
+
+    pub fn target(&self) -> Option<Target> {
+        self.target
+    }
+
+    /// Short text for the stack panel, e.g. "Fireball -> FOE SLOT 2", seen from the
+    /// player who played the card.
+    pub fn describe(&self) -> String {
+        let name = self.spell.data().name();
+        let (side, target_type) = match self.target {
+            Some(Target::Friendly(t)) => ("OWN", t),
+            Some(Target::Enemy(t)) => ("FOE", t),
+            None => return name,
+        };
+        match target_type {
+            TargetType::Hero => format!("{} -> {} HERO", name, side),
+            TargetType::Monster(slot) => format!("{} -> {} SLOT {}", name, side, slot),
+        }
+    }
+}
+
+/// Magic cards waiting to resolve, the last one played on top, and who may answer
+/// them. Playing a card counts as passing and hands priority to the other player.
+/// Once both players have passed in a row the top card resolves, and priority goes
+/// back to the player whose turn it is until the stack is empty.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct EffectStack {
+    entries: Vec<StackEntry>,
+    priority: Option<u8>,
+    passed: bool,
+}
+
+impl EffectStack {
+    /// Everything on the stack, bottom first.
+    pub fn entries(&self) -> &[StackEntry] {
+        &self.entries
+    }
+
+    pub fn top(&self) -> Option<&StackEntry> {
+        self.entries.last()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The player who has to respond or pass. None while the stack is empty.
+    pub fn priority(&self) -> Option<u8> {
+        self.priority
+    }
+}
+
+/// The card id of the monster at `target`, if it points at one.
+fn target_card(target: Option<Target>, caster: &Board, opponent: &Board) -> Option<u32> {
+    match target? {
+        Target::Friendly(TargetType::Monster(slot)) => caster.monster(slot).map(|m| m.id()),
+        Target::Enemy(TargetType::Monster(slot)) => opponent.monster(slot).map(|m| m.id()),
+        _ => None,
+    }
+}
+
+/// Whether the card's chosen target has gone away, or can't be picked any more.
+/// A card like that does nothing when it resolves.
+fn fizzles(entry: &StackEntry, caster: &Board, opponent: &Board) -> bool {
+    entry.target.is_some()
+        && (target_card(entry.target, caster, opponent) != entry.target_card
+            || !entry.spell.can_target(entry.target, caster, opponent))
+}
+
+/// Pays for the magic card at `card` in `player`'s hand and puts it on the stack.
+/// An empty stack takes any card but those that answer another one, a full one only
+/// instants from the player holding priority.
+pub fn cast(
+    ecs: &World,
+    player: u8,
+    card: usize,
+    target: Option<Target>,
+) -> Result<(), ActionError> {
+    let mut stack = ecs.write_resource::<EffectStack>();
+    let mut boards = ecs.write_storage::<Board>();
+    let (caster, opponent) =
+        match_cycle::split_boards(&mut boards, player).ok_or(ActionError::UnknownPlayer(player))?;
+    match caster.hand().get(card) {
+        Some(CardHolder::MagicCard(magic)) if stack.is_empty() && magic.is_response_only() => {
+            return Err(ActionError::StackEmpty)
+        }
+        Some(CardHolder::MagicCard(magic)) if !stack.is_empty() && !magic.is_instant() => {
+            return Err(ActionError::NotInstant)
+        }
+        Some(CardHolder::MagicCard(_)) => {}
+        _ => return Err(ActionError::CardNotInHand(card)),
+    }
+    if !stack.is_empty() && stack.priority != Some(player) {
+        return Err(ActionError::NotYourTurn);
+    }
+
+    let target_card = target_card(target, caster, opponent);
+    let spell = match caster.play_card(opponent, card, target)? {
+        Played::Magic(magic) => magic,
+        Played::Monster(_) | Played::Weapon => unreachable!("checked to be a magic card above"),
+    };
+    stack.entries.push(StackEntry {
+        controller: player,
+        spell,
+        target,
+        target_card,
+    });
+    stack.priority = Some(opponent.id());
+    stack.passed = true;
+    Ok(())
+}
+
+/// Resolves `entry` for its controller. Counter effects take the card right below it
+/// off the stack and put it in its owner's graveyard. A card with lingering abilities
+/// stays in play afterwards.
+fn resolve_entry(
+    entry: StackEntry,
+    below: &mut Vec<StackEntry>,
+    caster: &mut Board,
+    opponent: &mut Board,
+    db: &CardDatabase,
+    rng: &mut RandomNumberGenerator,
+) {
+    if fizzles(&entry, caster, opponent) {
+        caster.send_to_graveyard(CardHolder::MagicCard(entry.spell));
+    } else {
+        for effect in entry.spell.effects() {
+            if *effect.kind() == EffectKind::Counter && effect.target() == TargetSelector::Responded
+            {
+                if let Some(countered) = below.pop() {
+                    let owner = if countered.controller == entry.controller {
+                        &mut *caster
+                    } else {
+                        &mut *opponent
+                    };
+                    owner.send_to_graveyard(CardHolder::MagicCard(countered.spell));
+                }
+            }
+        }
+        effects::resolve_magic(&entry.spell, entry.target, caster, opponent, db, rng);
+        caster.settle_magic(entry.spell);
+    }
+    triggers::resolve_pending(caster, opponent, db, rng);
+}
+
+/// Passes priority for `player`. If the other player passed just before, the top card
+/// resolves instead.
+pub fn pass(ecs: &World, player: u8) -> Result<(), ActionError> {
+    let active = ecs.fetch::<Turn>().player();
+    let db = ecs.fetch::<CardDatabase>();
+    let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+    let mut stack = ecs.write_resource::<EffectStack>();
+    let mut boards = ecs.write_storage::<Board>();
+    if stack.is_empty() {
+        return Err(ActionError::StackEmpty);
+    }
+    if stack.priority != Some(player) {
+        return Err(ActionError::NotYourTurn);
+    }
+
+    if !stack.passed {
+        let other = (&boards).join().map(|b| b.id()).find(|id| *id != player);
+        stack.priority = other;
+        stack.passed = true;
+        return Ok(());
+    }
+
+    if let Some(entry) = stack.entries.pop() {
+        if let Some((caster, opponent)) = match_cycle::split_boards(&mut boards, entry.controller) {
+            resolve_entry(entry, &mut stack.entries, caster, opponent, &db, &mut rng);
+        }
+    }
+    stack.passed = false;
+    stack.priority = if stack.entries.is_empty() {
+        None
+    } else {
+        Some(active)
+    };
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use super::entities::{Board, MagicSchool, StatusKind};
+use super::matchups::MatchupTable;
+
+/// Damage a Lightning hit passes on to a neighbouring monster.
+pub const SHOCK_CHAIN_DAMAGE: i32 = 1;
+
+/// The status a hit of `school` leaves on the monster it damages. Physical hits leave
+/// nothing, and Light blesses the attacker instead (see `on_attack`).
+pub fn inflicted_by(school: MagicSchool) -> Option<StatusKind> {
+    match school {
+        MagicSchool::Flame => Some(StatusKind::Burn),
+        MagicSchool::Frost => Some(StatusKind::Freeze),
+        MagicSchool::Lightning => Some(StatusKind::Shock),
+        MagicSchool::Shadow => Some(StatusKind::Curse),
+        MagicSchool::Physical | MagicSchool::Light => None,
+    }
+}
+
+/// Hurts the monster in `slot` with `amount` of `school` after matchups, and leaves the
+/// school's status on it if any damage went through. Returns the damage dealt.
+pub fn hit_monster(
+    board: &mut Board,
+    slot: usize,
+    amount: i32,
+    school: MagicSchool,
+    matchups: &MatchupTable,
+) -> i32 {
+    let damage = match board.monster(slot) {
+        Some(monster) => matchups.damage_to_monster(amount, school, monster),
+        None => return 0,
+    };
+    board.damage_monster(slot, damage);
+    let inflicted = inflicted_by(school).filter(|_| damage > 0);
+    if let (Some(kind), Some(monster)) = (inflicted, board.monster_mut(slot)) {
+        let chains = kind == StatusKind::Shock && !monster.has_status(StatusKind::Shock);
+        monster.add_status(kind);
+        if chains {
+            chain_lightning(board, slot, matchups);
+        }
+    }
+    damage
+}
+
+/// A fresh shock jumps to the monster left of `slot`, or right of it if the left slot is empty.
+fn chain_lightning(board: &mut Board, slot: usize, matchups: &MatchupTable) {
+    if let Some(&neighbour) = board.neighbours(slot).first() {
+        let damage = board.monster(neighbour).map_or(0, |monster| {
+            matchups.damage_to_monster(SHOCK_CHAIN_DAMAGE, MagicSchool::Lightning, monster)
+        });
+        board.damage_monster(neighbour, damage);
+    }
+}
+
+/// Light monsters bless themselves whenever they attack.
+pub fn on_attack(board: &mut Board, slot: usize) {
+    if let Some(monster) = board.monster_mut(slot) {
+        if monster.school() == MagicSchool::Light {
+            monster.add_status(StatusKind::Blessing);
+        }
+    }
+}
+
+/// Burns and blessings take effect at the start of their owner's turn, and every
+/// status counts down one turn. Monsters that burn to death are left for the caller to remove.
+pub fn start_of_turn(board: &mut Board) {
+    for slot in 0..board.field().len() {
+        let burn = match board.monster_mut(slot) {
+            Some(monster) => {
+                let blessing = monster.status_stacks(StatusKind::Blessing);
+                monster.heal(blessing);
+                monster.age_statuses();
+                monster.status_stacks(StatusKind::Burn)
+            }
+            None => continue,
+        };
+        board.damage_monster(slot, burn);
+    }
+}
+
+/// Statuses whose last turn this was wear off as the owner's turn ends.
+pub fn end_of_turn(board: &mut Board) {
+    for monster in board.monsters_mut() {
+        monster.clear_expired_statuses();
+    }
+}
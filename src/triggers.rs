@@ -0,0 +1,110 @@
+use super::card_data::CardDatabase;
+use super::effects::{self, Origin};
+use super::entities::{Ability, Board, BoardEvent, Monster, Trigger, TurnPhase};
+use bracket_random::prelude::RandomNumberGenerator;
+
+/// Most abilities resolved in one go, so monsters can't keep setting each other off forever.
+pub const MAX_TRIGGERS: usize = 100;
+
+fn abilities_for(monster: &Monster, trigger: Trigger) -> Vec<Ability> {
+    monster
+        .abilities()
+        .iter()
+        .filter(|a| a.trigger() == trigger)
+        .cloned()
+        .collect()
+}
+
+/// Resolves the abilities `monster` in `slot` of `owner` has for `trigger`, in the
+/// order they are listed on its card. Returns how many went off.
+fn fire(
+    monster: &Monster,
+    slot: usize,
+    trigger: Trigger,
+    owner: &mut Board,
+    other: &mut Board,
+    db: &CardDatabase,
+    rng: &mut RandomNumberGenerator,
+) -> usize {
+    let abilities = abilities_for(monster, trigger);
+    for ability in abilities.iter() {
+        let origin = Origin::ability(monster.school(), slot);
+        effects::resolve(ability.effect(), origin, owner, other, db, rng);
+    }
+    abilities.len()
+}
+
+fn handle(
+    event: BoardEvent,
+    owner: &mut Board,
+    other: &mut Board,
+    db: &CardDatabase,
+    rng: &mut RandomNumberGenerator,
+) -> usize {
+    let (monster, slot, trigger) = match event {
+        BoardEvent::Played(slot) => (owner.monster(slot).cloned(), slot, Trigger::Played),
+        BoardEvent::Damaged(slot, monster) => (Some(monster), slot, Trigger::Damaged),
+        BoardEvent::Died(slot, monster) => (Some(monster), slot, Trigger::Died),
+    };
+    match monster {
+        Some(monster) => fire(&monster, slot, trigger, owner, other, db, rng),
+        None => 0,
+    }
+}
+
+/// Resolves the abilities set off by queued board events until nothing new happens.
+/// The board of the player whose turn it is goes first, each board's events go
+/// oldest first, and events raised along the way wait for the next round.
+pub fn resolve_pending(
+    active: &mut Board,
+    other: &mut Board,
+    db: &CardDatabase,
+    rng: &mut RandomNumberGenerator,
+) {
+    let mut resolved = 0;
+    loop {
+        active.remove_dead_monsters();
+        other.remove_dead_monsters();
+        let active_events = active.take_events();
+        let other_events = other.take_events();
+        if active_events.is_empty() && other_events.is_empty() {
+            return;
+        }
+        if resolved >= MAX_TRIGGERS {
+            return;
+        }
+        for event in active_events {
+            resolved += handle(event, active, other, db, rng);
+        }
+        for event in other_events {
+            resolved += handle(event, other, active, db, rng);
+        }
+    }
+}
+
+/// Runs the `phase` abilities of `owner`'s monsters from the leftmost slot on, then
+/// those of its lingering magic cards oldest first, then everything they set off.
+/// Called on the owner's own turn.
+pub fn phase(
+    phase: TurnPhase,
+    owner: &mut Board,
+    other: &mut Board,
+    db: &CardDatabase,
+    rng: &mut RandomNumberGenerator,
+) {
+    for slot in 0..owner.field().len() {
+        if let Some(monster) = owner.monster(slot).cloned() {
+            fire(&monster, slot, Trigger::Phase(phase), owner, other, db, rng);
+        }
+    }
+    for card in owner.lingering().to_vec() {
+        let magic = card.magic();
+        for ability in magic.abilities() {
+            if ability.trigger() == Trigger::Phase(phase) {
+                let origin = Origin::card(magic.school(), None);
+                effects::resolve(ability.effect(), origin, owner, other, db, rng);
+            }
+        }
+    }
+    resolve_pending(owner, other, db, rng);
+}
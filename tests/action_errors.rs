@@ -0,0 +1,177 @@
+mod common;
+
+use specs::prelude::*;
+use tcg_project::actions::{self, Action, ActionError};
+use tcg_project::entities::{MatchStatus, Target, TargetType};
+
+/// Player 0's first turn with one mana and, in hand, from the top: healing light,
+/// fireball, arcane insight, orc grunt and three wisps.
+fn setup() -> World {
+    common::match_with(
+        &[
+            "wisp",
+            "healing_light",
+            "fireball",
+            "arcane_insight",
+            "orc_grunt",
+            "wisp",
+            "wisp",
+        ],
+        &["wisp", "wisp", "wisp", "wisp", "wisp", "wisp"],
+    )
+}
+
+fn play(ecs: &mut World, card: usize, target: Option<Target>) -> Result<(), ActionError> {
+    actions::perform(ecs, 0, Action::PlayCard { card, target })
+}
+
+fn attack(ecs: &mut World, slot: usize, target: TargetType) -> Result<(), ActionError> {
+    actions::perform(ecs, 0, Action::Attack { slot, target })
+}
+
+/// Puts a ready copy of `id` into `slot` of `player`'s field.
+fn summon(ecs: &World, player: u8, slot: usize, id: &str) {
+    let db = common::cards();
+    common::with_board(ecs, player, |b| {
+        b.summon(slot, db.monster(id).expect("known monster").clone());
+        b.ready_monsters();
+    });
+}
+
+#[test]
+fn only_the_active_player_may_act_while_the_match_lasts() {
+    let mut ecs = setup();
+    assert_eq!(
+        actions::perform(&mut ecs, 1, Action::EndTurn),
+        Err(ActionError::NotYourTurn)
+    );
+    assert_eq!(
+        actions::perform(&mut ecs, 1, Action::Pass),
+        Err(ActionError::NotYourTurn)
+    );
+
+    *ecs.write_resource::<MatchStatus>() = MatchStatus::Won(1);
+    assert_eq!(
+        actions::perform(&mut ecs, 0, Action::EndTurn),
+        Err(ActionError::MatchOver)
+    );
+}
+
+#[test]
+fn cards_have_to_be_in_hand_and_paid_for() {
+    let mut ecs = setup();
+    let grunt = common::hand_index(&ecs, 0, "Orc Grunt");
+    assert_eq!(
+        play(&mut ecs, 99, None),
+        Err(ActionError::CardNotInHand(99))
+    );
+    assert_eq!(
+        play(&mut ecs, grunt, None),
+        Err(ActionError::NotEnoughResources {
+            cost: 3,
+            available: 1
+        })
+    );
+    common::with_board(&ecs, 0, |b| {
+        assert_eq!(b.hand().len(), 7);
+        assert_eq!(b.resources().current(), 1);
+        assert!(b.monster(0).is_none());
+    });
+}
+
+#[test]
+fn monsters_need_a_free_friendly_slot() {
+    let mut ecs = setup();
+    common::fill_mana(&ecs, 0);
+    let wisp = common::hand_index(&ecs, 0, "Wisp");
+    let own_slot = |slot| Some(Target::Friendly(TargetType::Monster(slot)));
+
+    assert!(play(&mut ecs, wisp, own_slot(0)).is_ok());
+    let wisp = common::hand_index(&ecs, 0, "Wisp");
+    assert_eq!(
+        play(&mut ecs, wisp, own_slot(0)),
+        Err(ActionError::SlotOccupied(0))
+    );
+    assert_eq!(
+        play(&mut ecs, wisp, own_slot(9)),
+        Err(ActionError::InvalidTarget(Target::Friendly(
+            TargetType::Monster(9)
+        )))
+    );
+    let enemy_hero = Target::Enemy(TargetType::Hero);
+    assert_eq!(
+        play(&mut ecs, wisp, Some(enemy_hero)),
+        Err(ActionError::InvalidTarget(enemy_hero))
+    );
+
+    for slot in 1..5 {
+        summon(&ecs, 0, slot, "orc_grunt");
+    }
+    assert_eq!(play(&mut ecs, wisp, None), Err(ActionError::NoFreeSlot));
+}
+
+#[test]
+fn magic_cards_take_a_target_only_when_they_need_one() {
+    let mut ecs = setup();
+    common::fill_mana(&ecs, 0);
+    let fireball = common::hand_index(&ecs, 0, "Fireball");
+    let insight = common::hand_index(&ecs, 0, "Arcane Insight");
+    let enemy_hero = Some(Target::Enemy(TargetType::Hero));
+
+    assert_eq!(
+        play(&mut ecs, fireball, None),
+        Err(ActionError::TargetRequired)
+    );
+    assert_eq!(
+        play(&mut ecs, insight, enemy_hero),
+        Err(ActionError::TargetNotAllowed)
+    );
+    let missing = Target::Enemy(TargetType::Monster(2));
+    assert_eq!(
+        play(&mut ecs, fireball, Some(missing)),
+        Err(ActionError::InvalidTarget(missing))
+    );
+    common::with_board(&ecs, 0, |b| assert_eq!(b.hand().len(), 7));
+}
+
+#[test]
+fn attacks_are_checked_before_anything_is_hit() {
+    let mut ecs = setup();
+    assert_eq!(
+        attack(&mut ecs, 3, TargetType::Hero),
+        Err(ActionError::NoMonster(3))
+    );
+
+    summon(&ecs, 0, 0, "orc_grunt");
+    summon(&ecs, 1, 2, "orc_grunt");
+    assert_eq!(
+        attack(&mut ecs, 0, TargetType::Monster(1)),
+        Err(ActionError::NoMonster(1))
+    );
+    assert_eq!(
+        attack(&mut ecs, 0, TargetType::Monster(2)),
+        Err(ActionError::OutOfRange)
+    );
+
+    summon(&ecs, 1, 0, "shadow_stalker");
+    assert_eq!(
+        attack(&mut ecs, 0, TargetType::Monster(0)),
+        Err(ActionError::TargetStealthed)
+    );
+
+    summon(&ecs, 1, 1, "ogre_brute");
+    assert_eq!(
+        attack(&mut ecs, 0, TargetType::Hero),
+        Err(ActionError::GuardInTheWay)
+    );
+    common::with_board(&ecs, 1, |b| assert_eq!(b.hero().health(), 30));
+
+    let db = common::cards();
+    common::with_board(&ecs, 0, |b| {
+        b.summon(1, db.monster("orc_grunt").unwrap().clone())
+    });
+    assert_eq!(
+        attack(&mut ecs, 1, TargetType::Monster(1)),
+        Err(ActionError::CannotAttack(1))
+    );
+}
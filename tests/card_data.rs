@@ -0,0 +1,140 @@
+mod common;
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use tcg_project::card_data::{self, CardDataError, CardDatabase, CARD_DATA_DIR};
+use tcg_project::entities::HeroClass;
+
+/// Loads the bundled card files with `contents` written to the file `name` next to
+/// them, replacing a bundled file of that name.
+fn load_with(name: &str, contents: &str) -> Result<CardDatabase, CardDataError> {
+    let dir = env::temp_dir().join(format!(
+        "tcg_project_cards_{}_{}",
+        name.replace('.', "_"),
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    for entry in fs::read_dir(CARD_DATA_DIR).unwrap() {
+        let path = entry.unwrap().path();
+        fs::copy(&path, dir.join(path.file_name().unwrap())).unwrap();
+    }
+    fs::write(dir.join(name), contents).unwrap();
+    let result = card_data::load_card_database(&dir);
+    fs::remove_dir_all(&dir).unwrap();
+    result
+}
+
+/// A card file holding one plain monster called `id`.
+fn monster_file(id: &str) -> String {
+    format!(
+        "(monsters: [(id: \"{}\", name: \"Fixture\", base_cost: 1, base_health: 1, \
+         base_damage: 1, attack_type: (range: 0, school: Physical))])",
+        id
+    )
+}
+
+fn file_name(path: &Path) -> &str {
+    path.file_name().unwrap().to_str().unwrap()
+}
+
+#[test]
+fn the_bundled_card_data_loads() {
+    let db = common::cards();
+    assert!(db.monster("wisp").is_some());
+    assert!(db.hero_power(HeroClass::Mage).is_some());
+}
+
+#[test]
+fn ids_can_only_be_used_once() {
+    match load_with("duplicate.ron", &monster_file("wisp")) {
+        Err(CardDataError::Duplicate { id, first, second }) => {
+            assert_eq!(id, "wisp");
+            let mut files = [file_name(&first), file_name(&second)];
+            files.sort_unstable();
+            assert_eq!(files, ["duplicate.ron", "monsters.ron"]);
+        }
+        other => panic!("expected a duplicate id, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn ids_cant_be_empty() {
+    match load_with("empty_id.ron", &monster_file(" ")) {
+        Err(CardDataError::Invalid { path, id, .. }) => {
+            assert_eq!((file_name(&path), id.as_str()), ("empty_id.ron", " "))
+        }
+        other => panic!("expected an invalid card, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn summons_have_to_name_a_known_monster() {
+    let file = "(magic: [(id: \"bad_summon\", name: \"Bad Summon\", base_cost: 1, \
+                effects: [(kind: Summon(\"no_such_monster\"), target: Hero(Friendly))])])";
+    match load_with("dangling.ron", file) {
+        Err(CardDataError::Invalid { path, id, reason }) => {
+            assert_eq!(
+                (file_name(&path), id.as_str()),
+                ("dangling.ron", "bad_summon")
+            );
+            assert!(reason.contains("no_such_monster"), "{}", reason);
+        }
+        other => panic!("expected an invalid card, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn malformed_files_name_the_file() {
+    for (name, contents) in [
+        ("broken.ron", "(monsters: [(id: \"half\","),
+        ("broken.json", "{\"monsters\": [{\"id\": \"half\",}]}"),
+    ] {
+        match load_with(name, contents) {
+            Err(CardDataError::Parse { path, .. }) => assert_eq!(file_name(&path), name),
+            other => panic!("expected a parse error, got {:?}", other.err()),
+        }
+    }
+}
+
+#[test]
+fn every_class_needs_a_hero_power() {
+    let file = "(hero_powers: [])";
+    assert!(matches!(
+        load_with("heroes.ron", file),
+        Err(CardDataError::MissingHeroPower(_))
+    ));
+}
+
+#[test]
+fn a_missing_folder_is_an_io_error() {
+    let dir = env::temp_dir().join(format!("tcg_project_no_cards_{}", std::process::id()));
+    assert!(matches!(
+        card_data::load_card_database(&dir),
+        Err(CardDataError::Io { .. })
+    ));
+}
+
+#[test]
+fn only_lingering_magic_has_turn_phase_abilities() {
+    let ability = |trigger: &str| {
+        format!(
+            "abilities: [(trigger: {}, effect: (kind: Draw(1), target: Hero(Friendly)))]",
+            trigger
+        )
+    };
+    for (id, extra) in [
+        ("no_lingers", ability("Phase(PrePlay)")),
+        ("no_abilities", "lingers: 2".to_string()),
+        ("played", format!("{}, lingers: 2", ability("Played"))),
+    ] {
+        let file = format!(
+            "(magic: [(id: \"{}\", name: \"Bad\", base_cost: 1, effects: [], {})])",
+            id, extra
+        );
+        match load_with("lingering.ron", &file) {
+            Err(CardDataError::Invalid { id: bad, .. }) => assert_eq!(bad, id),
+            other => panic!("expected an invalid card, got {:?}", other.err()),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use tcg_project::actions::ActionError;
+use tcg_project::combat;
+use tcg_project::entities::{Board, TargetType};
+use tcg_project::matchups::MatchupTable;
+
+fn health(board: &Board, slot: usize) -> Option<i32> {
+    board.monster(slot).map(|m| m.health())
+}
+
+#[test]
+fn monsters_reach_as_many_columns_as_their_range() {
+    assert!(combat::in_range(2, 2, 0));
+    assert!(!combat::in_range(2, 3, 0));
+    assert!(combat::in_range(2, 0, 2));
+    assert!(!combat::in_range(4, 1, 2));
+}
+
+#[test]
+fn melee_monsters_only_reach_the_facing_column() {
+    let table = MatchupTable::default();
+    let mut own = common::field(0, &[Some("orc_grunt")]);
+    let mut enemy = common::field(1, &[None, Some("orc_grunt")]);
+    assert_eq!(
+        combat::attack(&mut own, &mut enemy, 0, TargetType::Monster(1), &table),
+        Err(ActionError::OutOfRange)
+    );
+    assert_eq!(
+        combat::attack(&mut own, &mut enemy, 0, TargetType::Monster(4), &table),
+        Err(ActionError::NoMonster(4))
+    );
+    assert_eq!(health(&enemy, 1), Some(3));
+    assert!(own.monster(0).unwrap().can_attack());
+}
+
+#[test]
+fn defenders_in_reach_strike_back() {
+    let table = MatchupTable::default();
+    let mut own = common::field(0, &[None, Some("orc_grunt")]);
+    let mut enemy = common::field(1, &[None, Some("wisp")]);
+    assert!(combat::attack(&mut own, &mut enemy, 1, TargetType::Monster(1), &table).is_ok());
+    assert_eq!(health(&enemy, 1), None);
+    assert_eq!(health(&own, 1), Some(2));
+    assert_eq!(
+        combat::attack(&mut own, &mut enemy, 1, TargetType::Hero, &table),
+        Err(ActionError::CannotAttack(1))
+    );
+}
+
+#[test]
+fn attackers_out_of_the_defenders_reach_take_nothing_back() {
+    let table = MatchupTable::default();
+    let mut own = common::field(0, &[Some("storm_archer")]);
+    let mut enemy = common::field(1, &[None, None, Some("orc_grunt")]);
+    assert!(combat::attack(&mut own, &mut enemy, 0, TargetType::Monster(2), &table).is_ok());
+    assert_eq!(health(&enemy, 2), Some(1));
+    assert_eq!(health(&own, 0), Some(2));
+}
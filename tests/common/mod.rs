@@ -0,0 +1,156 @@
+//! Setup shared by the integration tests.
+#![allow(dead_code)]
+
+use bracket_random::prelude::RandomNumberGenerator;
+use specs::prelude::*;
+use std::path::Path;
+use tcg_project::card_data::{self, CardDatabase, CARD_DATA_DIR};
+use tcg_project::deck::{self, DECK_DIR};
+use tcg_project::entities::{Board, Hero, HeroClass, MatchStatus};
+use tcg_project::match_log::MatchLog;
+use tcg_project::mulligan::{self, Mulligan};
+use tcg_project::{actions, ai, match_cycle};
+
+/// The card pool shipped in the data folder.
+pub fn cards() -> CardDatabase {
+    card_data::load_card_database(Path::new(CARD_DATA_DIR)).expect("card data loads")
+}
+
+/// A board for `player` with a copy of each of `ids` in the matching field slot, `None`
+/// leaving the slot empty. Every monster is ready to attack.
+pub fn field(player: u8, ids: &[Option<&str>]) -> Board {
+    let db = cards();
+    let mut board = Board::new(player, Hero::new(player, 30, 30, HeroClass::Mage), vec![]);
+    for (slot, id) in ids.iter().enumerate() {
+        if let Some(id) = id {
+            board.summon(slot, db.monster(id).expect("known monster").clone());
+        }
+    }
+    board.ready_monsters();
+    board
+}
+
+/// A board for `player` whose deck holds the cards named by `ids`. Cards are drawn
+/// from the end of the list, so the last six make up the opening hand.
+pub fn board(db: &CardDatabase, player: u8, ids: &[&str]) -> Board {
+    let deck = ids
+        .iter()
+        .enumerate()
+        .map(|(n, id)| db.card(id, n as u32).expect("known card id"))
+        .collect();
+    Board::new(player, Hero::new(player, 30, 30, HeroClass::Mage), deck)
+}
+
+/// A fresh world with the card data loaded, its randomness drawn from `seed`.
+pub fn world(seed: u64) -> World {
+    let mut ecs = match_cycle::new_world();
+    card_data::initialize_card_data(&mut ecs).expect("card data loads");
+    match_cycle::seed_match(&mut ecs, seed);
+    ecs
+}
+
+/// A match between decks of `ids0` and `ids1`, run on to player 0's first InPlay phase,
+/// see `start`.
+pub fn match_with(ids0: &[&str], ids1: &[&str]) -> World {
+    seeded_match(1, ids0, ids1)
+}
+
+/// Like `match_with`, with everything random in the match drawn from `seed`.
+pub fn seeded_match(seed: u64, ids0: &[&str], ids1: &[&str]) -> World {
+    let mut ecs = world(seed);
+    let (board0, board1) = {
+        let db = ecs.fetch::<CardDatabase>();
+        (board(&db, 0, ids0), board(&db, 1, ids1))
+    };
+    start(&mut ecs, board0, board1);
+    ecs
+}
+
+/// Starts a match between the two boards, keeps both opening hands without the extra
+/// card for the second player, and runs on to the first player's InPlay phase.
+pub fn start(ecs: &mut World, board0: Board, board1: Board) {
+    ecs.write_resource::<Mulligan>().set_compensation(false);
+    match_cycle::start_match(ecs, board0, board1);
+    mulligan::keep(ecs, 0).expect("player 0 keeps");
+    mulligan::keep(ecs, 1).expect("player 1 keeps");
+    match_cycle::advance(ecs);
+}
+
+/// A match between the first two deck lists in the deck folder, shuffled from
+/// `seed`, with both opening hands still to be decided.
+pub fn deck_match(seed: u64) -> World {
+    let mut ecs = world(seed);
+    let decks = deck::load_decks(Path::new(DECK_DIR)).expect("deck lists load");
+    let (deck0, deck1) = {
+        let db = ecs.fetch::<CardDatabase>();
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        (
+            decks[0].1.build(&db, &mut rng).expect("valid deck"),
+            decks[1].1.build(&db, &mut rng).expect("valid deck"),
+        )
+    };
+    let board0 = Board::new(0, Hero::new(0, 30, 30, decks[0].1.class()), deck0);
+    let board1 = Board::new(1, Hero::new(1, 30, 30, decks[1].1.class()), deck1);
+    match_cycle::start_match(&mut ecs, board0, board1);
+    ecs
+}
+
+/// Lets the computer play both sides for up to `turns` turns, or until the match
+/// is over.
+pub fn play_ai(ecs: &mut World, turns: u32) {
+    ai::mulligan(ecs, 0);
+    ai::mulligan(ecs, 1);
+    for _ in 0..turns * 4 {
+        match_cycle::advance(ecs);
+        if ecs.fetch::<MatchStatus>().is_over() {
+            return;
+        }
+        if let Some(player) = match_cycle::acting_player(ecs) {
+            ai::take_turn(ecs, player);
+        }
+    }
+}
+
+/// Runs `f` on `player`'s board.
+pub fn with_board<R>(ecs: &World, player: u8, f: impl FnOnce(&mut Board) -> R) -> R {
+    let mut boards = ecs.write_storage::<Board>();
+    let board = (&mut boards)
+        .join()
+        .find(|b| b.id() == player)
+        .expect("board for player");
+    f(board)
+}
+
+/// Where the first card called `name` sits in `player`'s hand.
+pub fn hand_index(ecs: &World, player: u8, name: &str) -> usize {
+    with_board(ecs, player, |b| {
+        b.hand()
+            .iter()
+            .position(|c| c.name() == name)
+            .expect("card in hand")
+    })
+}
+
+/// Fills `player`'s mana pool up to its limit.
+pub fn fill_mana(ecs: &World, player: u8) {
+    with_board(ecs, player, |b| {
+        for _ in 0..10 {
+            b.resources_mut().grow_and_refill();
+        }
+    });
+}
+
+/// Performs `action` for `player`, expecting the rules to allow it.
+pub fn perform(ecs: &mut World, player: u8, action: actions::Action) {
+    actions::perform(ecs, player, action).expect("action allowed");
+}
+
+/// Everything on both boards and in the match log, to compare two matches by.
+pub fn snapshot(ecs: &World) -> String {
+    let boards = ecs.read_storage::<Board>();
+    let mut boards: Vec<&Board> = (&boards).join().collect();
+    boards.sort_by_key(|b| b.id());
+    let boards = serde_json::to_string(&boards).expect("boards serialize");
+    let log = serde_json::to_string(&*ecs.fetch::<MatchLog>()).expect("log serializes");
+    format!("{}\n{}", boards, log)
+}
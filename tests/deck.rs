@@ -0,0 +1,129 @@
+mod common;
+
+use bracket_random::prelude::RandomNumberGenerator;
+use common::cards;
+use std::env;
+use std::fs;
+use std::path::Path;
+use tcg_project::deck::{self, DeckError, DeckList, DECK_DIR, DECK_SIZE, MAX_COPIES};
+use tcg_project::entities::HeroClass;
+
+/// A legal deck of ten different cards, three copies each.
+fn full_deck() -> DeckList {
+    let ids = [
+        "wisp",
+        "orc_grunt",
+        "ogre_brute",
+        "shadow_stalker",
+        "frost_sentinel",
+        "storm_archer",
+        "dawn_cleric",
+        "rallying_cry",
+        "healing_light",
+        "fireball",
+    ];
+    let mut deck = DeckList::new("Test".to_string(), HeroClass::Warrior);
+    for id in ids.iter() {
+        for _ in 0..MAX_COPIES {
+            deck.add_copy(id);
+        }
+    }
+    deck
+}
+
+#[test]
+fn bundled_decks_are_legal() {
+    let db = cards();
+    let decks = deck::load_decks(Path::new(DECK_DIR)).unwrap();
+    assert!(decks.len() >= 2);
+    for (path, deck) in decks {
+        assert!(deck.validate(&db).is_ok(), "{}", path.display());
+    }
+}
+
+#[test]
+fn unknown_cards_are_rejected() {
+    let mut deck = full_deck();
+    deck.remove_copy("wisp");
+    deck.add_copy("no_such_card");
+    match deck.validate(&cards()) {
+        Err(DeckError::UnknownCard(id)) => assert_eq!(id, "no_such_card"),
+        other => panic!("expected an unknown card, got {:?}", other),
+    }
+}
+
+#[test]
+fn class_cards_only_go_in_decks_of_their_class() {
+    let mut deck = full_deck();
+    deck.remove_copy("fireball");
+    deck.add_copy("pyroblast");
+    match deck.validate(&cards()) {
+        Err(DeckError::WrongClass { id, class }) => {
+            assert_eq!((id.as_str(), class), ("pyroblast", HeroClass::Mage))
+        }
+        other => panic!("expected a wrong class card, got {:?}", other),
+    }
+
+    let mut deck = full_deck();
+    deck.remove_copy("fireball");
+    deck.add_copy("shield_block");
+    assert!(deck.validate(&cards()).is_ok());
+}
+
+#[test]
+fn more_than_max_copies_are_rejected() {
+    let mut deck = full_deck();
+    deck.remove_copy("fireball");
+    deck.add_copy("wisp");
+    match deck.validate(&cards()) {
+        Err(DeckError::TooManyCopies { id, copies }) => {
+            assert_eq!((id.as_str(), copies), ("wisp", MAX_COPIES + 1))
+        }
+        other => panic!("expected too many copies, got {:?}", other),
+    }
+}
+
+#[test]
+fn decks_must_hold_exactly_deck_size_cards() {
+    let db = cards();
+    let mut deck = full_deck();
+    assert_eq!(deck.size(), DECK_SIZE);
+    assert!(deck.validate(&db).is_ok());
+
+    deck.remove_copy("fireball");
+    assert!(matches!(
+        deck.validate(&db),
+        Err(DeckError::WrongSize(size)) if size == DECK_SIZE - 1
+    ));
+    let mut rng = RandomNumberGenerator::seeded(1);
+    assert!(matches!(
+        deck.build(&db, &mut rng),
+        Err(DeckError::WrongSize(_))
+    ));
+}
+
+#[test]
+fn built_decks_hold_every_copy() {
+    let db = cards();
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let built = full_deck().build(&db, &mut rng).unwrap();
+    assert_eq!(built.len(), DECK_SIZE);
+    let wisps = built.iter().filter(|c| c.name() == "Wisp").count();
+    assert_eq!(wisps as u32, MAX_COPIES);
+}
+
+#[test]
+fn saved_decks_load_back_unchanged() {
+    let deck = full_deck();
+    let path = env::temp_dir().join(format!("tcg_project_deck_{}.ron", std::process::id()));
+    deck::save_deck(&deck, &path).unwrap();
+    assert_eq!(deck::load_deck(&path).unwrap(), deck);
+
+    fs::write(&path, "(name: \"Broken\", class: Warrior").unwrap();
+    assert!(matches!(
+        deck::load_deck(&path),
+        Err(DeckError::Parse { .. })
+    ));
+    fs::remove_file(&path).unwrap();
+    assert!(matches!(deck::load_deck(&path), Err(DeckError::Io { .. })));
+}
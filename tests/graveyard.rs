@@ -0,0 +1,98 @@
+mod common;
+
+use tcg_project::actions::Action;
+use tcg_project::entities::{Board, Card, CardHolder};
+
+/// Kills the monster in `slot` the way combat does.
+fn kill(board: &mut Board, slot: usize) {
+    board.monster_mut(slot).expect("monster in slot").destroy();
+    board.remove_dead_monsters();
+}
+
+fn names(board: &Board) -> Vec<String> {
+    board.graveyard().iter().map(|c| c.name()).collect()
+}
+
+#[test]
+fn dead_monsters_are_buried_face_up_in_order() {
+    let mut board = common::field(1, &[Some("wisp"), Some("ogre_brute")]);
+    assert!(board.graveyard().is_empty());
+    kill(&mut board, 1);
+    kill(&mut board, 0);
+    assert_eq!(names(&board), vec!["Ogre Brute", "Wisp"]);
+    assert!(board.graveyard().iter().all(|c| !c.hidden()));
+}
+
+#[test]
+fn recovering_takes_back_the_latest_card() {
+    let mut board = common::field(1, &[Some("wisp"), Some("ogre_brute")]);
+    assert!(!board.recover_from_graveyard());
+    kill(&mut board, 0);
+    kill(&mut board, 1);
+
+    assert!(board.recover_from_graveyard());
+    assert_eq!(names(&board), vec!["Wisp"]);
+    let card = board.hand().last().expect("card back in hand");
+    assert_eq!(card.name(), "Ogre Brute");
+    assert!(!card.hidden());
+}
+
+#[test]
+fn resurrected_monsters_come_back_as_printed() {
+    let db = common::cards();
+    let mut board = common::field(0, &[Some("ogre_brute")]);
+    board.monster_mut(0).unwrap().take_damage(4);
+    kill(&mut board, 0);
+    board.send_to_graveyard(db.card("fireball", 9).unwrap());
+    board.summon(1, db.monster("wisp").unwrap().clone());
+
+    assert!(!board.resurrect(1));
+    assert!(board.resurrect(0));
+    let ogre = board.monster(0).expect("ogre back on the field");
+    assert_eq!(
+        (ogre.data().name(), ogre.health()),
+        ("Ogre Brute".to_string(), 6)
+    );
+    assert_eq!(names(&board), vec!["Fireball"]);
+    assert!(!board.resurrect(2));
+}
+
+#[test]
+fn raise_dead_returns_the_latest_monster_to_the_field() {
+    let mut ecs = common::match_with(
+        &["wisp", "wisp", "wisp", "wisp", "wisp", "raise_dead"],
+        &["wisp", "wisp", "wisp", "wisp", "wisp", "wisp"],
+    );
+    let db = common::cards();
+    common::fill_mana(&ecs, 0);
+    common::with_board(&ecs, 0, |b| {
+        b.summon(2, db.monster("orc_grunt").unwrap().clone());
+        kill(b, 2);
+    });
+
+    let card = common::hand_index(&ecs, 0, "Raise Dead");
+    common::perform(&mut ecs, 0, Action::PlayCard { card, target: None });
+    common::perform(&mut ecs, 1, Action::Pass);
+    common::with_board(&ecs, 0, |b| {
+        let grunt = b.monster(0).expect("grunt raised into the first free slot");
+        assert_eq!(grunt.data().name(), "Orc Grunt");
+        assert_eq!(names(b), vec!["Raise Dead"]);
+    });
+}
+
+#[test]
+fn returned_monsters_go_back_to_hand_face_up_as_printed() {
+    let mut board = common::field(1, &[Some("ogre_brute")]);
+    board.monster_mut(0).unwrap().take_damage(4);
+    assert!(!board.return_to_hand(1));
+    assert!(board.return_to_hand(0));
+    assert!(board.monster(0).is_none());
+    assert!(board.graveyard().is_empty());
+    match board.hand().last() {
+        Some(CardHolder::MonsterCard(ogre)) => {
+            assert_eq!(ogre.health(), 6);
+            assert!(!ogre.hidden());
+        }
+        _ => panic!("expected the ogre back in hand"),
+    }
+}
@@ -0,0 +1,157 @@
+mod common;
+
+use specs::prelude::*;
+use std::env;
+use tcg_project::actions::{self, Action, ActionError};
+use tcg_project::card_data::CardDatabase;
+use tcg_project::entities::{Target, TargetType};
+use tcg_project::match_cycle;
+use tcg_project::match_log::MatchLog;
+use tcg_project::{ai, saveload_system};
+
+/// Like `common::match_with`, with both hands capped at `size` cards.
+fn capped(ids0: &[&str], ids1: &[&str], size: usize) -> World {
+    let mut ecs = common::world(5);
+    let (mut board0, mut board1) = {
+        let db = ecs.fetch::<CardDatabase>();
+        (common::board(&db, 0, ids0), common::board(&db, 1, ids1))
+    };
+    board0.set_max_hand_size(size);
+    board1.set_max_hand_size(size);
+    common::start(&mut ecs, board0, board1);
+    ecs
+}
+
+fn hand_len(ecs: &World, player: u8) -> usize {
+    common::with_board(ecs, player, |b| b.hand().len())
+}
+
+fn log(ecs: &World) -> Vec<(u32, u8, String)> {
+    let log = ecs.fetch::<MatchLog>();
+    log.entries()
+        .iter()
+        .map(|e| (e.turn(), e.player(), e.event().describe()))
+        .collect()
+}
+
+#[test]
+fn drawing_into_a_full_hand_burns_the_card() {
+    let ids = [
+        "fireball",
+        "wisp",
+        "orc_grunt",
+        "wisp",
+        "orc_grunt",
+        "wisp",
+        "orc_grunt",
+        "wisp",
+        "orc_grunt",
+        "wisp",
+    ];
+    let ecs = capped(&ids, &ids, 6);
+    assert_eq!(hand_len(&ecs, 0), 6);
+    common::with_board(&ecs, 0, |b| {
+        assert_eq!(b.graveyard().len(), 1);
+        assert_eq!(b.graveyard()[0].id(), 3);
+        assert!(!b.graveyard()[0].hidden());
+    });
+    assert_eq!(log(&ecs), vec![(1, 0, "BURNED Wisp".to_string())]);
+}
+
+#[test]
+fn forced_discards_come_before_anything_else() {
+    let ids0 = [
+        "wisp",
+        "wisp",
+        "wisp",
+        "wisp",
+        "orc_grunt",
+        "wisp",
+        "wisp",
+        "wisp",
+        "wisp",
+        "wisp",
+        "mind_rot",
+    ];
+    let ids1 = [
+        "wisp",
+        "wisp",
+        "wisp",
+        "wisp",
+        "fireball",
+        "frost_nova",
+        "wisp",
+        "orc_grunt",
+        "healing_light",
+    ];
+    let mut ecs = capped(&ids0, &ids1, 10);
+    assert_eq!(
+        actions::perform(&mut ecs, 0, Action::Discard { card: 0 }),
+        Err(ActionError::NothingToDiscard)
+    );
+    common::fill_mana(&ecs, 0);
+    let mind_rot = common::hand_index(&ecs, 0, "Mind Rot");
+    common::perform(
+        &mut ecs,
+        0,
+        Action::PlayCard {
+            card: mind_rot,
+            target: None,
+        },
+    );
+    common::perform(&mut ecs, 1, Action::Pass);
+    assert_eq!(common::with_board(&ecs, 1, |b| b.discards_pending()), 2);
+    assert_eq!(match_cycle::acting_player(&ecs), Some(1));
+    assert_eq!(
+        actions::perform(&mut ecs, 0, Action::EndTurn),
+        Err(ActionError::NotYourTurn)
+    );
+    assert_eq!(
+        actions::perform(&mut ecs, 1, Action::EndTurn),
+        Err(ActionError::MustDiscard)
+    );
+    assert_eq!(
+        actions::perform(&mut ecs, 1, Action::Discard { card: 9 }),
+        Err(ActionError::CardNotInHand(9))
+    );
+
+    let first = common::with_board(&ecs, 1, |b| b.hand()[0].name());
+    common::perform(&mut ecs, 1, Action::Discard { card: 0 });
+    assert_eq!(common::with_board(&ecs, 1, |b| b.discards_pending()), 1);
+    assert_eq!(log(&ecs), vec![(1, 1, format!("DISCARDED {}", first))]);
+
+    let path = env::temp_dir().join(format!("tcg_project_discards_{}.json", std::process::id()));
+    saveload_system::save_game(&mut ecs, &path).unwrap();
+    saveload_system::load_game(&mut ecs, &path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(common::with_board(&ecs, 1, |b| b.discards_pending()), 1);
+
+    ai::take_turn(&mut ecs, 1);
+    assert_eq!(common::with_board(&ecs, 1, |b| b.discards_pending()), 0);
+    assert_eq!(hand_len(&ecs, 1), 4);
+    assert_eq!(log(&ecs).len(), 2);
+    assert_eq!(match_cycle::acting_player(&ecs), Some(0));
+}
+
+#[test]
+fn random_discards_are_logged_for_the_player_who_lost_the_card() {
+    let ids1 = ["wisp", "wisp", "wisp", "wisp", "wisp", "wisp", "pickpocket"];
+    let mut ecs = capped(&["wisp"; 8], &ids1, 10);
+    common::perform(&mut ecs, 0, Action::EndTurn);
+    match_cycle::advance(&mut ecs);
+    assert_eq!(match_cycle::acting_player(&ecs), Some(1));
+
+    common::fill_mana(&ecs, 1);
+    let before = hand_len(&ecs, 0);
+    let pickpocket = common::hand_index(&ecs, 1, "Pickpocket");
+    common::perform(
+        &mut ecs,
+        1,
+        Action::PlayCard {
+            card: pickpocket,
+            target: Some(Target::Friendly(TargetType::Monster(0))),
+        },
+    );
+    assert_eq!(hand_len(&ecs, 0), before - 1);
+    assert_eq!(log(&ecs), vec![(2, 0, "DISCARDED Wisp".to_string())]);
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use specs::prelude::*;
+use tcg_project::actions::{self, Action, ActionError};
+use tcg_project::entities::{Target, TargetType};
+use tcg_project::match_cycle;
+
+fn fireblast(ecs: &mut World, target: Option<Target>) -> Result<(), ActionError> {
+    actions::perform(ecs, 0, Action::HeroPower { target })
+}
+
+fn end_turn(ecs: &mut World, player: u8) {
+    common::perform(ecs, player, Action::EndTurn);
+    match_cycle::advance(ecs);
+}
+
+#[test]
+fn the_hero_power_works_once_per_turn() {
+    let wisps = ["wisp"; 10];
+    let mut ecs = common::match_with(&wisps, &wisps);
+    let enemy_hero = Some(Target::Enemy(TargetType::Hero));
+    assert_eq!(
+        fireblast(&mut ecs, enemy_hero),
+        Err(ActionError::NotEnoughResources {
+            cost: 2,
+            available: 1
+        })
+    );
+
+    common::fill_mana(&ecs, 0);
+    assert_eq!(fireblast(&mut ecs, None), Err(ActionError::TargetRequired));
+    assert!(fireblast(&mut ecs, enemy_hero).is_ok());
+    assert_eq!(fireblast(&mut ecs, enemy_hero), Err(ActionError::PowerUsed));
+    common::with_board(&ecs, 0, |b| assert_eq!(b.resources().current(), 8));
+    common::with_board(&ecs, 1, |b| assert_eq!(b.hero().health(), 29));
+
+    end_turn(&mut ecs, 0);
+    end_turn(&mut ecs, 1);
+    common::fill_mana(&ecs, 0);
+    assert!(fireblast(&mut ecs, enemy_hero).is_ok());
+    common::with_board(&ecs, 1, |b| assert_eq!(b.hero().health(), 28));
+}
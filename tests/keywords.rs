@@ -0,0 +1,76 @@
+mod common;
+
+use tcg_project::actions::ActionError;
+use tcg_project::combat;
+use tcg_project::entities::TargetType;
+use tcg_project::matchups::MatchupTable;
+
+#[test]
+fn guards_have_to_be_attacked_first() {
+    let table = MatchupTable::default();
+    let mut own = common::field(0, &[Some("orc_grunt"), Some("orc_grunt")]);
+    let mut enemy = common::field(1, &[Some("wisp"), Some("ogre_brute")]);
+
+    assert_eq!(
+        combat::attack(&mut own, &mut enemy, 0, TargetType::Hero, &table),
+        Err(ActionError::GuardInTheWay)
+    );
+    assert_eq!(
+        combat::attack(&mut own, &mut enemy, 0, TargetType::Monster(0), &table),
+        Err(ActionError::GuardInTheWay)
+    );
+    assert!(combat::attack(&mut own, &mut enemy, 1, TargetType::Monster(1), &table).is_ok());
+    assert_eq!(enemy.monster(1).unwrap().health(), 3);
+    assert_eq!(enemy.monster(0).unwrap().health(), 1);
+}
+
+#[test]
+fn ranged_monsters_attack_past_guards() {
+    let table = MatchupTable::default();
+    let mut own = common::field(0, &[Some("storm_archer")]);
+    let mut enemy = common::field(1, &[Some("wisp"), None, Some("ogre_brute")]);
+
+    assert!(combat::attack(&mut own, &mut enemy, 0, TargetType::Hero, &table).is_ok());
+    assert_eq!(enemy.hero().health(), 28);
+    own.ready_monsters();
+    assert!(combat::attack(&mut own, &mut enemy, 0, TargetType::Monster(0), &table).is_ok());
+    assert!(enemy.monster(0).is_none());
+}
+
+#[test]
+fn only_hasty_monsters_attack_the_turn_they_arrive() {
+    let db = common::cards();
+    let mut board = common::field(0, &[]);
+    board.summon(0, db.monster("wisp").unwrap().clone());
+    board.summon(1, db.monster("orc_grunt").unwrap().clone());
+    assert!(board.monster(0).unwrap().can_attack());
+    assert!(!board.monster(1).unwrap().can_attack());
+}
+
+#[test]
+fn lifesteal_heals_the_hero_both_ways() {
+    let table = MatchupTable::default();
+    let mut own = common::field(0, &[Some("dawn_cleric")]);
+    let mut enemy = common::field(1, &[Some("dawn_cleric")]);
+    own.hero_mut().take_damage(5);
+    enemy.hero_mut().take_damage(5);
+
+    assert!(combat::attack(&mut own, &mut enemy, 0, TargetType::Monster(0), &table).is_ok());
+    assert_eq!(own.hero().health(), 26);
+    assert_eq!(enemy.hero().health(), 26);
+}
+
+#[test]
+fn stealth_lasts_until_the_monster_attacks() {
+    let table = MatchupTable::default();
+    let mut own = common::field(0, &[Some("orc_grunt")]);
+    let mut enemy = common::field(1, &[Some("shadow_stalker")]);
+
+    assert_eq!(
+        combat::attack(&mut own, &mut enemy, 0, TargetType::Monster(0), &table),
+        Err(ActionError::TargetStealthed)
+    );
+    assert!(combat::attack(&mut enemy, &mut own, 0, TargetType::Hero, &table).is_ok());
+    assert!(combat::attack(&mut own, &mut enemy, 0, TargetType::Monster(0), &table).is_ok());
+    assert!(enemy.monster(0).is_none());
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use tcg_project::actions::{self, Action, ActionError};
+use tcg_project::entities::{MatchStatus, TargetType};
+use tcg_project::match_cycle;
+
+#[test]
+fn lethal_attack_ends_the_match_at_once() {
+    let mut ecs = common::match_with(&["wisp"; 8], &["wisp"; 8]);
+    let grunt = common::cards().monster("orc_grunt").unwrap().clone();
+    common::with_board(&ecs, 0, |board| {
+        board.summon(2, grunt);
+        board.ready_monsters();
+    });
+    common::with_board(&ecs, 1, |board| board.hero_mut().take_damage(29));
+
+    let attack = Action::Attack {
+        slot: 2,
+        target: TargetType::Hero,
+    };
+    assert_eq!(actions::perform(&mut ecs, 0, attack), Ok(()));
+    assert_eq!(*ecs.fetch::<MatchStatus>(), MatchStatus::Won(0));
+    assert!(!match_cycle::can_act(&ecs, 0));
+    assert_eq!(
+        actions::perform(&mut ecs, 0, Action::EndTurn),
+        Err(ActionError::MatchOver)
+    );
+    assert_eq!(
+        actions::perform(&mut ecs, 1, Action::Pass),
+        Err(ActionError::MatchOver)
+    );
+}
+
+#[test]
+fn both_heroes_dying_is_a_draw() {
+    let mut ecs = common::match_with(&["wisp"; 8], &["wisp"; 8]);
+    for player in 0..2 {
+        common::with_board(&ecs, player, |board| board.hero_mut().take_damage(30));
+    }
+    match_cycle::advance(&mut ecs);
+    assert_eq!(*ecs.fetch::<MatchStatus>(), MatchStatus::Drawn);
+}
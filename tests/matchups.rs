@@ -0,0 +1,82 @@
+mod common;
+
+use common::cards;
+use std::env;
+use std::fs;
+use tcg_project::card_data::{self, CardDataError, CardDatabase};
+use tcg_project::entities::{Affinity, Hero, HeroClass, MagicSchool, Monster};
+use tcg_project::matchups::MatchupTable;
+
+fn monster(db: &CardDatabase, id: &str) -> Monster {
+    Monster::new(0, db.monster(id).expect("known monster").clone())
+}
+
+#[test]
+fn damage_scales_by_the_table_percentages_rounding_down() {
+    let table: MatchupTable = ron::de::from_str("(resistant: 50, weak: 150, immune: 0)").unwrap();
+    assert_eq!(table.scale(4, Affinity::Normal), 4);
+    assert_eq!(table.scale(4, Affinity::Resistant), 2);
+    assert_eq!(table.scale(3, Affinity::Resistant), 1);
+    assert_eq!(table.scale(1, Affinity::Resistant), 0);
+    assert_eq!(table.scale(3, Affinity::Weak), 4);
+    assert_eq!(table.scale(5, Affinity::Immune), 0);
+    assert_eq!(table.scale(0, Affinity::Weak), 0);
+}
+
+#[test]
+fn printed_defenses_win_over_school_defaults() {
+    let db = cards();
+    let table = db.matchups();
+    let wisp = monster(&db, "wisp");
+    let sentinel = monster(&db, "frost_sentinel");
+    let grunt = monster(&db, "orc_grunt");
+
+    assert_eq!(table.damage_to_monster(6, MagicSchool::Flame, &wisp), 0);
+    assert_eq!(table.damage_to_monster(3, MagicSchool::Flame, &sentinel), 6);
+    assert_eq!(
+        table.damage_to_monster(5, MagicSchool::Physical, &sentinel),
+        2
+    );
+    assert_eq!(table.damage_to_monster(3, MagicSchool::Frost, &sentinel), 1);
+    assert_eq!(table.damage_to_monster(3, MagicSchool::Flame, &grunt), 3);
+}
+
+#[test]
+fn hero_classes_resist_their_schools() {
+    let table = cards().matchups().clone();
+    let hero = |class| Hero::new(0, 30, 30, class);
+
+    assert_eq!(
+        table.damage_to_hero(4, MagicSchool::Frost, &hero(HeroClass::Mage)),
+        2
+    );
+    assert_eq!(
+        table.damage_to_hero(4, MagicSchool::Frost, &hero(HeroClass::Warrior)),
+        4
+    );
+    assert_eq!(
+        table.damage_to_hero(4, MagicSchool::Light, &hero(HeroClass::Priest)),
+        0
+    );
+    assert_eq!(
+        table.damage_to_hero(4, MagicSchool::Shadow, &hero(HeroClass::Priest)),
+        8
+    );
+}
+
+#[test]
+fn negative_percentages_are_rejected() {
+    let dir = env::temp_dir().join(format!("tcg_project_matchups_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("matchups.ron"),
+        "(matchups: (resistant: -50, weak: 200, immune: 0))",
+    )
+    .unwrap();
+    let result = card_data::load_card_database(&dir);
+    fs::remove_dir_all(&dir).unwrap();
+    match result {
+        Err(CardDataError::Invalid { id, .. }) => assert_eq!(id, "matchups"),
+        other => panic!("expected invalid matchups, got {:?}", other.err()),
+    }
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use tcg_project::entities::{Monster, Stat};
+
+fn grunt() -> Monster {
+    Monster::new(0, common::cards().monster("orc_grunt").unwrap().clone())
+}
+
+#[test]
+fn set_overrides_earlier_buffs_only() {
+    let mut monster = grunt();
+    monster.buff(0, 2);
+    assert_eq!(monster.damage(), 5);
+    monster.set_stat(Stat::Damage, 1);
+    assert_eq!(monster.damage(), 1);
+    monster.buff(0, 1);
+    assert_eq!(monster.damage(), 2);
+}
+
+#[test]
+fn turn_buffs_wear_off_under_a_later_set() {
+    let mut monster = grunt();
+    monster.set_stat(Stat::Damage, 1);
+    monster.buff_until_end_of_turn(2, 2);
+    assert_eq!((monster.damage(), monster.health()), (3, 5));
+    monster.end_turn_modifiers();
+    assert_eq!((monster.damage(), monster.health()), (1, 3));
+}
+
+#[test]
+fn setting_health_heals_and_lowering_it_keeps_damage() {
+    let mut monster = grunt();
+    monster.buff(2, 0);
+    monster.take_damage(4);
+    assert_eq!(monster.health(), 1);
+    monster.set_stat(Stat::Health, 4);
+    assert_eq!((monster.health(), monster.max_health()), (4, 4));
+    monster.take_damage(1);
+    monster.set_stat(Stat::Health, 2);
+    assert_eq!((monster.health(), monster.max_health()), (2, 2));
+}
+
+#[test]
+fn silence_clears_every_modifier() {
+    let mut monster = grunt();
+    monster.set_stat(Stat::Damage, 7);
+    monster.buff(1, 1);
+    monster.silence();
+    assert_eq!((monster.damage(), monster.max_health()), (3, 3));
+    assert!(monster.modifiers().is_empty());
+}
+
+#[test]
+fn auras_stay_on_top_of_a_set() {
+    let mut board = common::field(0, &[None, Some("orc_grunt"), Some("war_banner")]);
+    assert_eq!(board.monster(1).unwrap().damage(), 4);
+    board.monster_mut(1).unwrap().set_stat(Stat::Damage, 1);
+    assert_eq!(board.monster(1).unwrap().damage(), 2);
+    board.exile_monster(2);
+    assert_eq!(board.monster(1).unwrap().damage(), 1);
+}
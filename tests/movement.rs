@@ -0,0 +1,69 @@
+mod common;
+
+use specs::prelude::*;
+use tcg_project::actions::{self, Action, ActionError};
+use tcg_project::entities::{Card, StatusKind, MOVE_COST};
+
+/// A match where player 0 has mana to move with, a grunt in slot 0 and a wisp in
+/// slot 1 of their field.
+fn two_monsters() -> World {
+    let ecs = common::match_with(&["wisp"; 8], &["wisp"; 8]);
+    let db = common::cards();
+    common::with_board(&ecs, 0, |board| {
+        board.summon(0, db.monster("orc_grunt").unwrap().clone());
+        board.summon(1, db.monster("wisp").unwrap().clone());
+        for _ in 0..2 {
+            board.resources_mut().grow_and_refill();
+        }
+    });
+    ecs
+}
+
+fn name_at(ecs: &World, slot: usize) -> Option<String> {
+    common::with_board(ecs, 0, |b| b.monster(slot).map(|m| m.data().name()))
+}
+
+#[test]
+fn moving_into_a_free_slot_costs_mana_once_per_turn() {
+    let mut ecs = two_monsters();
+    let mana = common::with_board(&ecs, 0, |b| b.resources().current());
+    actions::perform(&mut ecs, 0, Action::Move { slot: 0, to: 3 }).unwrap();
+    assert_eq!(name_at(&ecs, 3).as_deref(), Some("Orc Grunt"));
+    assert_eq!(name_at(&ecs, 0), None);
+    assert_eq!(
+        common::with_board(&ecs, 0, |b| b.resources().current()),
+        mana - MOVE_COST
+    );
+    assert_eq!(
+        actions::perform(&mut ecs, 0, Action::Move { slot: 3, to: 4 }),
+        Err(ActionError::CannotMove(3))
+    );
+}
+
+#[test]
+fn swapped_monsters_use_up_their_move() {
+    let mut ecs = two_monsters();
+    actions::perform(&mut ecs, 0, Action::Move { slot: 0, to: 1 }).unwrap();
+    assert_eq!(name_at(&ecs, 0).as_deref(), Some("Wisp"));
+    assert_eq!(name_at(&ecs, 1).as_deref(), Some("Orc Grunt"));
+    assert_eq!(
+        actions::perform(&mut ecs, 0, Action::Move { slot: 0, to: 2 }),
+        Err(ActionError::CannotMove(0))
+    );
+}
+
+#[test]
+fn frozen_monsters_cant_be_swapped() {
+    let mut ecs = two_monsters();
+    common::with_board(&ecs, 0, |b| {
+        b.monster_mut(1).unwrap().add_status(StatusKind::Freeze)
+    });
+    assert_eq!(
+        actions::perform(&mut ecs, 0, Action::Move { slot: 0, to: 1 }),
+        Err(ActionError::CannotMove(1))
+    );
+    assert_eq!(
+        actions::perform(&mut ecs, 0, Action::Move { slot: 1, to: 0 }),
+        Err(ActionError::CannotMove(1))
+    );
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use specs::prelude::*;
+use tcg_project::actions::{self, Action, ActionError};
+use tcg_project::card_data::{self, CardDatabase};
+use tcg_project::match_cycle;
+use tcg_project::mulligan::{self, Mulligan};
+
+const IDS: [&str; 12] = [
+    "wisp",
+    "orc_grunt",
+    "fireball",
+    "frost_nova",
+    "healing_light",
+    "arcane_insight",
+    "ogre_brute",
+    "wisp",
+    "orc_grunt",
+    "fireball",
+    "frost_nova",
+    "healing_light",
+];
+
+/// A match dealt out and waiting on both opening hands.
+fn dealt(compensation: bool) -> World {
+    let mut ecs = match_cycle::new_world();
+    card_data::initialize_card_data(&mut ecs).expect("card data loads");
+    let (board0, board1) = {
+        let db = ecs.fetch::<CardDatabase>();
+        (common::board(&db, 0, &IDS), common::board(&db, 1, &IDS))
+    };
+    ecs.write_resource::<Mulligan>()
+        .set_compensation(compensation);
+    match_cycle::start_match(&mut ecs, board0, board1);
+    match_cycle::advance(&mut ecs);
+    ecs
+}
+
+fn hand_ids(ecs: &World, player: u8) -> Vec<u32> {
+    common::with_board(ecs, player, |b| b.hand().iter().map(|c| c.id()).collect())
+}
+
+#[test]
+fn nobody_acts_before_both_hands_are_kept() {
+    let mut ecs = dealt(true);
+    assert_eq!(match_cycle::acting_player(&ecs), None);
+    assert_eq!(
+        actions::perform(&mut ecs, 0, Action::EndTurn),
+        Err(ActionError::NotYourTurn)
+    );
+    mulligan::keep(&ecs, 0).unwrap();
+    match_cycle::advance(&mut ecs);
+    assert_eq!(match_cycle::acting_player(&ecs), None);
+    mulligan::keep(&ecs, 1).unwrap();
+    match_cycle::advance(&mut ecs);
+    assert_eq!(match_cycle::acting_player(&ecs), Some(0));
+}
+
+#[test]
+fn replaced_cards_go_back_for_new_ones() {
+    let ecs = dealt(true);
+    let before = hand_ids(&ecs, 0);
+    assert_eq!(before.len(), 6);
+    assert_eq!(
+        mulligan::replace(&ecs, 0, &[9]),
+        Err(ActionError::CardNotInHand(9))
+    );
+    assert_eq!(hand_ids(&ecs, 0), before);
+
+    mulligan::replace(&ecs, 0, &[1, 3, 3]).unwrap();
+    let after = hand_ids(&ecs, 0);
+    assert_eq!(after.len(), 6);
+    assert_eq!(&after[..4], &[before[0], before[2], before[4], before[5]]);
+    assert!(!after[4..].contains(&before[1]) && !after[4..].contains(&before[3]));
+    assert_eq!(common::with_board(&ecs, 0, |b| b.count_deck_size()), 6);
+
+    assert_eq!(mulligan::keep(&ecs, 0), Err(ActionError::HandKept));
+    assert_eq!(mulligan::replace(&ecs, 0, &[0]), Err(ActionError::HandKept));
+    assert!(ecs.fetch::<Mulligan>().is_waiting(1));
+}
+
+#[test]
+fn the_second_player_gets_an_extra_card_unless_turned_off() {
+    for (compensation, size) in [(true, 7), (false, 6)] {
+        let ecs = dealt(compensation);
+        mulligan::keep(&ecs, 0).unwrap();
+        mulligan::keep(&ecs, 1).unwrap();
+        assert_eq!(common::with_board(&ecs, 0, |b| b.hand().len()), 6);
+        assert_eq!(common::with_board(&ecs, 1, |b| b.hand().len()), size);
+    }
+}
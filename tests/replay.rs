@@ -0,0 +1,70 @@
+mod common;
+
+use specs::prelude::*;
+use tcg_project::actions::Action;
+use tcg_project::card_data::CardDatabase;
+use tcg_project::entities::MatchSeed;
+use tcg_project::match_cycle;
+
+/// Player 0 casts Arcane Missiles into three enemy grunts, letting it pick its own
+/// targets, and ends the turn.
+fn scripted(seed: u64) -> World {
+    let hand = [
+        "wisp",
+        "wisp",
+        "arcane_missiles",
+        "orc_grunt",
+        "wisp",
+        "wisp",
+        "wisp",
+    ];
+    let mut ecs = common::seeded_match(seed, &hand, &hand);
+    let grunt = ecs
+        .fetch::<CardDatabase>()
+        .monster("orc_grunt")
+        .unwrap()
+        .clone();
+    common::with_board(&ecs, 1, |b| {
+        for slot in 0..3 {
+            b.summon(slot, grunt.clone());
+        }
+    });
+    common::fill_mana(&ecs, 0);
+    let card = common::hand_index(&ecs, 0, "Arcane Missiles");
+    common::perform(&mut ecs, 0, Action::PlayCard { card, target: None });
+    common::perform(&mut ecs, 1, Action::Pass);
+    common::perform(&mut ecs, 0, Action::EndTurn);
+    match_cycle::advance(&mut ecs);
+    ecs
+}
+
+#[test]
+fn same_seed_and_actions_give_the_same_match() {
+    for seed in [1, 2, 3] {
+        assert_eq!(
+            common::snapshot(&scripted(seed)),
+            common::snapshot(&scripted(seed))
+        );
+    }
+    let outcomes: Vec<String> = (0..8)
+        .map(|seed| common::snapshot(&scripted(seed)))
+        .collect();
+    assert!(outcomes.iter().any(|o| *o != outcomes[0]));
+}
+
+#[test]
+fn computer_matches_replay_from_their_seed() {
+    for seed in [1, 7, 12345] {
+        let mut first = common::deck_match(seed);
+        let mut second = common::deck_match(seed);
+        common::play_ai(&mut first, 40);
+        common::play_ai(&mut second, 40);
+        assert_eq!(common::snapshot(&first), common::snapshot(&second));
+        assert_eq!(first.fetch::<MatchSeed>().0, seed);
+    }
+    let mut first = common::deck_match(1);
+    let mut second = common::deck_match(2);
+    common::play_ai(&mut first, 40);
+    common::play_ai(&mut second, 40);
+    assert_ne!(common::snapshot(&first), common::snapshot(&second));
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use specs::prelude::*;
+use tcg_project::actions::{self, Action, ActionError};
+use tcg_project::entities::{ResourcePool, MAX_RESOURCES};
+use tcg_project::match_cycle;
+
+fn mana(ecs: &World, player: u8) -> (i32, i32) {
+    common::with_board(ecs, player, |b| {
+        (b.resources().current(), b.resources().maximum())
+    })
+}
+
+#[test]
+fn pools_grow_by_one_up_to_the_cap_and_refill() {
+    let mut pool = ResourcePool::new();
+    assert_eq!((pool.current(), pool.maximum()), (0, 0));
+    pool.grow_and_refill();
+    pool.grow_and_refill();
+    assert!(pool.spend(2));
+    assert_eq!((pool.current(), pool.maximum()), (0, 2));
+    assert!(!pool.spend(1));
+    assert_eq!(pool.current(), 0);
+
+    pool.grow_and_refill();
+    assert_eq!((pool.current(), pool.maximum()), (3, 3));
+    for _ in 0..20 {
+        pool.grow_and_refill();
+    }
+    assert_eq!(
+        (pool.current(), pool.maximum()),
+        (MAX_RESOURCES, MAX_RESOURCES)
+    );
+}
+
+#[test]
+fn cards_cost_mana_and_the_pool_refills_each_turn() {
+    let ids = [
+        "wisp",
+        "wisp",
+        "wisp",
+        "wisp",
+        "orc_grunt",
+        "orc_grunt",
+        "wisp",
+        "wisp",
+    ];
+    let mut ecs = common::match_with(&ids, &ids);
+    assert_eq!(mana(&ecs, 0), (1, 1));
+    assert_eq!(mana(&ecs, 1), (0, 0));
+
+    let grunt = common::hand_index(&ecs, 0, "Orc Grunt");
+    assert_eq!(
+        actions::perform(
+            &mut ecs,
+            0,
+            Action::PlayCard {
+                card: grunt,
+                target: None
+            }
+        ),
+        Err(ActionError::NotEnoughResources {
+            cost: 3,
+            available: 1
+        })
+    );
+    let wisp = common::hand_index(&ecs, 0, "Wisp");
+    common::perform(
+        &mut ecs,
+        0,
+        Action::PlayCard {
+            card: wisp,
+            target: None,
+        },
+    );
+    assert_eq!(mana(&ecs, 0), (0, 1));
+
+    common::perform(&mut ecs, 0, Action::EndTurn);
+    match_cycle::advance(&mut ecs);
+    assert_eq!(mana(&ecs, 1), (1, 1));
+    common::perform(&mut ecs, 1, Action::EndTurn);
+    match_cycle::advance(&mut ecs);
+    assert_eq!(mana(&ecs, 0), (2, 2));
+}
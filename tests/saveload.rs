@@ -0,0 +1,80 @@
+mod common;
+
+use specs::prelude::*;
+use std::fs;
+use std::path::PathBuf;
+use tcg_project::entities::{Board, MatchSeed, MatchStatus, Turn, MAX_HAND_SIZE};
+use tcg_project::saveload_system;
+
+fn save_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("tcg_project_{}_{}.json", name, std::process::id()))
+}
+
+fn hand_ids(ecs: &World, player: u8) -> Vec<u32> {
+    common::with_board(ecs, player, |b| b.hand().iter().map(|c| c.id()).collect())
+}
+
+#[test]
+fn unreadable_save_leaves_the_match_alone() {
+    let mut ecs = common::seeded_match(7, &["wisp"; 12], &["wisp"; 12]);
+    let path = save_path("unreadable");
+    saveload_system::save_game(&mut ecs, &path).unwrap();
+    let saved = fs::read_to_string(&path).unwrap();
+    let hand = hand_ids(&ecs, 0);
+
+    let broken = [
+        saved[..saved.len() / 2].to_string(),
+        saved.replace("\"fatigue\"", "\"tiredness\""),
+        "{}".to_string(),
+    ];
+    for contents in broken.iter() {
+        fs::write(&path, contents).unwrap();
+        assert!(saveload_system::load_game(&mut ecs, &path).is_err());
+        assert_eq!(ecs.read_storage::<Board>().join().count(), 2);
+        assert_eq!(hand_ids(&ecs, 0), hand);
+        assert_eq!(ecs.fetch::<Turn>().number(), 1);
+    }
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn saves_from_before_the_hand_limit_still_load() {
+    let mut ecs = common::seeded_match(7, &["wisp"; 12], &["wisp"; 12]);
+    let path = save_path("old");
+    saveload_system::save_game(&mut ecs, &path).unwrap();
+    let saved = fs::read_to_string(&path).unwrap();
+    let old = saved
+        .replace(",\"max_hand_size\":10", "")
+        .replace(",\"discards\":0", "");
+    assert!(!old.contains("max_hand_size") && !old.contains("discards"));
+    fs::write(&path, old).unwrap();
+
+    common::with_board(&ecs, 0, |b| b.set_max_hand_size(3));
+    saveload_system::load_game(&mut ecs, &path).unwrap();
+    assert_eq!(
+        common::with_board(&ecs, 0, |b| b.max_hand_size()),
+        MAX_HAND_SIZE
+    );
+    assert_eq!(common::with_board(&ecs, 0, |b| b.discards_pending()), 0);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn a_loaded_match_plays_on_like_the_saved_one() {
+    let mut ecs = common::deck_match(11);
+    common::play_ai(&mut ecs, 5);
+    let path = save_path("round_trip");
+    saveload_system::save_game(&mut ecs, &path).unwrap();
+
+    let mut loaded = common::world(0);
+    saveload_system::load_game(&mut loaded, &path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(common::snapshot(&loaded), common::snapshot(&ecs));
+    assert_eq!(*loaded.fetch::<Turn>(), *ecs.fetch::<Turn>());
+    assert_eq!(loaded.fetch::<MatchSeed>().0, 11);
+
+    common::play_ai(&mut ecs, 30);
+    common::play_ai(&mut loaded, 30);
+    assert_eq!(common::snapshot(&loaded), common::snapshot(&ecs));
+    assert_eq!(*loaded.fetch::<MatchStatus>(), *ecs.fetch::<MatchStatus>());
+}
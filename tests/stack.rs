@@ -0,0 +1,137 @@
+mod common;
+
+use specs::prelude::*;
+use tcg_project::actions::{self, Action, ActionError};
+use tcg_project::entities::{Target, TargetType};
+use tcg_project::match_cycle;
+use tcg_project::stack::EffectStack;
+
+/// Player 0 holds a fireball and wisps, player 1 a counterspell, a quick shot, a
+/// fireball and wisps, and player 1 has a Wisp in slot 0. Both have full mana.
+fn setup() -> World {
+    let ids0 = ["wisp", "wisp", "wisp", "wisp", "wisp", "wisp", "fireball"];
+    let ids1 = [
+        "wisp",
+        "wisp",
+        "wisp",
+        "fireball",
+        "counterspell",
+        "quick_shot",
+    ];
+    let ecs = common::match_with(&ids0, &ids1);
+    let wisp = common::cards().monster("wisp").unwrap().clone();
+    common::with_board(&ecs, 1, |b| b.summon(0, wisp));
+    common::fill_mana(&ecs, 0);
+    common::fill_mana(&ecs, 1);
+    ecs
+}
+
+fn cast(ecs: &World, player: u8, name: &str, target: Option<Target>) -> Action {
+    Action::PlayCard {
+        card: common::hand_index(ecs, player, name),
+        target,
+    }
+}
+
+fn hero_health(ecs: &World, player: u8) -> i32 {
+    common::with_board(ecs, player, |b| b.hero().health())
+}
+
+#[test]
+fn a_card_resolves_once_the_opponent_passes() {
+    let mut ecs = setup();
+    let fireball = cast(&ecs, 0, "Fireball", Some(Target::Enemy(TargetType::Hero)));
+    common::perform(&mut ecs, 0, fireball);
+    assert_eq!(match_cycle::acting_player(&ecs), Some(1));
+    assert_eq!(hero_health(&ecs, 1), 30);
+
+    common::perform(&mut ecs, 1, Action::Pass);
+    assert_eq!(hero_health(&ecs, 1), 24);
+    assert!(ecs.fetch::<EffectStack>().is_empty());
+    assert_eq!(match_cycle::acting_player(&ecs), Some(0));
+}
+
+#[test]
+fn the_last_card_played_resolves_first() {
+    let mut ecs = setup();
+    let fireball = cast(
+        &ecs,
+        0,
+        "Fireball",
+        Some(Target::Enemy(TargetType::Monster(0))),
+    );
+    common::perform(&mut ecs, 0, fireball);
+    let shot = cast(
+        &ecs,
+        1,
+        "Quick Shot",
+        Some(Target::Friendly(TargetType::Monster(0))),
+    );
+    common::perform(&mut ecs, 1, shot);
+    assert_eq!(ecs.fetch::<EffectStack>().entries().len(), 2);
+
+    common::perform(&mut ecs, 0, Action::Pass);
+    assert!(common::with_board(&ecs, 1, |b| b.monster(0).is_none()));
+    assert_eq!(match_cycle::acting_player(&ecs), Some(0));
+
+    let wisp = common::cards().monster("wisp").unwrap().clone();
+    common::with_board(&ecs, 1, |b| b.summon(0, wisp));
+    common::perform(&mut ecs, 0, Action::Pass);
+    common::perform(&mut ecs, 1, Action::Pass);
+    assert!(ecs.fetch::<EffectStack>().is_empty());
+    assert!(common::with_board(&ecs, 1, |b| b.monster(0).is_some()));
+}
+
+#[test]
+fn a_countered_card_does_nothing() {
+    let mut ecs = setup();
+    let fireball = cast(&ecs, 0, "Fireball", Some(Target::Enemy(TargetType::Hero)));
+    common::perform(&mut ecs, 0, fireball);
+    let counterspell = cast(&ecs, 1, "Counterspell", None);
+    common::perform(&mut ecs, 1, counterspell);
+
+    common::perform(&mut ecs, 0, Action::Pass);
+    assert!(ecs.fetch::<EffectStack>().is_empty());
+    assert_eq!(hero_health(&ecs, 1), 30);
+    assert_eq!(match_cycle::acting_player(&ecs), Some(0));
+}
+
+#[test]
+fn only_the_player_with_priority_answers_and_only_with_instants() {
+    let mut ecs = setup();
+    assert_eq!(
+        actions::perform(&mut ecs, 0, Action::Pass),
+        Err(ActionError::StackEmpty)
+    );
+    let fireball = cast(&ecs, 0, "Fireball", Some(Target::Enemy(TargetType::Hero)));
+    common::perform(&mut ecs, 0, fireball);
+
+    assert_eq!(
+        actions::perform(&mut ecs, 0, Action::Pass),
+        Err(ActionError::NotYourTurn)
+    );
+    assert_eq!(
+        actions::perform(&mut ecs, 0, Action::EndTurn),
+        Err(ActionError::NotYourTurn)
+    );
+    let wisp = cast(
+        &ecs,
+        1,
+        "Wisp",
+        Some(Target::Friendly(TargetType::Monster(1))),
+    );
+    assert_eq!(
+        actions::perform(&mut ecs, 1, wisp),
+        Err(ActionError::StackNotEmpty)
+    );
+    let fireball = cast(&ecs, 1, "Fireball", Some(Target::Enemy(TargetType::Hero)));
+    assert_eq!(
+        actions::perform(&mut ecs, 1, fireball),
+        Err(ActionError::NotInstant)
+    );
+    assert_eq!(
+        actions::perform(&mut ecs, 1, Action::EndTurn),
+        Err(ActionError::StackNotEmpty)
+    );
+    assert_eq!(ecs.fetch::<EffectStack>().entries().len(), 1);
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use tcg_project::entities::{Board, MagicSchool, StatusKind};
+use tcg_project::matchups::MatchupTable;
+use tcg_project::status;
+
+fn health(board: &Board, slot: usize) -> i32 {
+    board.monster(slot).expect("monster in slot").health()
+}
+
+/// Runs the owner's turn boundaries: the start of one turn, then its end.
+fn owner_turn(board: &mut Board) {
+    status::start_of_turn(board);
+    status::end_of_turn(board);
+}
+
+#[test]
+fn burns_tick_at_the_start_of_two_owner_turns() {
+    let table = MatchupTable::default();
+    let mut board = common::field(0, &[Some("ogre_brute")]);
+    assert_eq!(
+        status::hit_monster(&mut board, 0, 1, MagicSchool::Flame, &table),
+        1
+    );
+    assert_eq!(health(&board, 0), 5);
+    assert_eq!(board.monster(0).unwrap().status_stacks(StatusKind::Burn), 1);
+
+    owner_turn(&mut board);
+    assert_eq!(health(&board, 0), 4);
+    owner_turn(&mut board);
+    assert_eq!(health(&board, 0), 3);
+    assert!(!board.monster(0).unwrap().has_status(StatusKind::Burn));
+    owner_turn(&mut board);
+    assert_eq!(health(&board, 0), 3);
+}
+
+#[test]
+fn burn_stacks_are_capped() {
+    let table = MatchupTable::default();
+    let mut board = common::field(0, &[Some("ogre_brute")]);
+    for _ in 0..5 {
+        status::hit_monster(&mut board, 0, 0, MagicSchool::Flame, &table);
+        status::hit_monster(&mut board, 0, 1, MagicSchool::Flame, &table);
+    }
+    assert_eq!(
+        board.monster(0).unwrap().status_stacks(StatusKind::Burn),
+        StatusKind::Burn.max_stacks()
+    );
+}
+
+#[test]
+fn frozen_monsters_skip_their_next_attack() {
+    let table = MatchupTable::default();
+    let mut board = common::field(0, &[Some("ogre_brute")]);
+    status::hit_monster(&mut board, 0, 1, MagicSchool::Frost, &table);
+    assert!(!board.monster(0).unwrap().can_attack());
+
+    status::start_of_turn(&mut board);
+    board.ready_monsters();
+    assert!(!board.monster(0).unwrap().can_attack());
+    status::end_of_turn(&mut board);
+
+    status::start_of_turn(&mut board);
+    assert!(board.monster(0).unwrap().can_attack());
+}
+
+#[test]
+fn a_fresh_shock_chains_to_one_neighbour() {
+    let table = MatchupTable::default();
+    let mut board = common::field(0, &[None, Some("ogre_brute"), Some("ogre_brute")]);
+    status::hit_monster(&mut board, 1, 2, MagicSchool::Lightning, &table);
+    assert_eq!(health(&board, 1), 4);
+    assert_eq!(health(&board, 2), 6 - status::SHOCK_CHAIN_DAMAGE);
+
+    status::hit_monster(&mut board, 1, 2, MagicSchool::Lightning, &table);
+    assert_eq!(health(&board, 1), 2);
+    assert_eq!(health(&board, 2), 6 - status::SHOCK_CHAIN_DAMAGE);
+
+    let mut board = common::field(
+        0,
+        &[Some("ogre_brute"), Some("ogre_brute"), Some("ogre_brute")],
+    );
+    status::hit_monster(&mut board, 1, 2, MagicSchool::Lightning, &table);
+    assert_eq!(health(&board, 0), 6 - status::SHOCK_CHAIN_DAMAGE);
+    assert_eq!(health(&board, 2), 6);
+}
+
+#[test]
+fn resisted_hits_leave_no_status() {
+    let table: MatchupTable = ron::de::from_str("(resistant: 0, weak: 200, immune: 0)").unwrap();
+    let mut board = common::field(0, &[Some("wisp")]);
+    status::hit_monster(&mut board, 0, 3, MagicSchool::Flame, &table);
+    assert_eq!(health(&board, 0), 1);
+    assert!(board.monster(0).unwrap().statuses().is_empty());
+}
+
+#[test]
+fn curses_weaken_attacks_until_they_wear_off() {
+    let table = MatchupTable::default();
+    let mut board = common::field(0, &[Some("ogre_brute")]);
+    status::hit_monster(&mut board, 0, 1, MagicSchool::Shadow, &table);
+    status::hit_monster(&mut board, 0, 1, MagicSchool::Shadow, &table);
+    assert_eq!(board.monster(0).unwrap().damage(), 3);
+
+    owner_turn(&mut board);
+    assert_eq!(board.monster(0).unwrap().damage(), 3);
+    owner_turn(&mut board);
+    assert_eq!(board.monster(0).unwrap().damage(), 5);
+}
+
+#[test]
+fn light_attackers_bless_themselves_and_heal() {
+    let table = MatchupTable::default();
+    let mut board = common::field(0, &[Some("dawn_cleric"), Some("ogre_brute")]);
+    status::on_attack(&mut board, 1);
+    assert!(!board.monster(1).unwrap().has_status(StatusKind::Blessing));
+
+    status::hit_monster(&mut board, 0, 2, MagicSchool::Physical, &table);
+    status::on_attack(&mut board, 0);
+    assert_eq!(health(&board, 0), 1);
+
+    owner_turn(&mut board);
+    assert_eq!(health(&board, 0), 2);
+    owner_turn(&mut board);
+    assert_eq!(health(&board, 0), 3);
+    owner_turn(&mut board);
+    assert_eq!(health(&board, 0), 3);
+    assert!(board.monster(0).unwrap().statuses().is_empty());
+}
@@ -0,0 +1,234 @@
+mod common;
+
+use bracket_random::prelude::RandomNumberGenerator;
+use specs::prelude::*;
+use tcg_project::actions::Action;
+use tcg_project::entities::{Board, Card, CardHolder, Magic, MagicData, MonsterData, TurnPhase};
+use tcg_project::match_cycle;
+use tcg_project::triggers::{self, MAX_TRIGGERS};
+
+/// A monster card with the given `health` and RON `abilities`.
+fn monster(health: i32, abilities: &str) -> MonsterData {
+    ron::de::from_str(&format!(
+        "(id: \"test\", name: \"Test\", base_cost: 1, base_health: {}, base_damage: 1, \
+         attack_type: (range: 0, school: Physical), abilities: [{}])",
+        health, abilities
+    ))
+    .expect("valid monster")
+}
+
+/// A magic card staying in play for `turns` with the given RON `abilities`.
+fn lingering(turns: u32, abilities: &str) -> Magic {
+    let data: MagicData = ron::de::from_str(&format!(
+        "(id: \"test\", name: \"Test\", base_cost: 1, effects: [], abilities: [{}], \
+         lingers: {})",
+        abilities, turns
+    ))
+    .expect("valid magic card");
+    Magic::new(0, data)
+}
+
+fn names(board: &Board) -> Vec<Option<String>> {
+    board
+        .field()
+        .iter()
+        .map(|m| m.as_ref().map(|m| m.data().name()))
+        .collect()
+}
+
+#[test]
+fn dying_monsters_leave_their_summons_behind() {
+    let db = common::cards();
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let mut own = common::field(0, &[None, Some("bone_golem")]);
+    let mut enemy = common::field(1, &[]);
+    own.damage_monster(1, 10);
+    triggers::resolve_pending(&mut own, &mut enemy, &db, &mut rng);
+    assert_eq!(names(&own)[1].as_deref(), Some("Wisp"));
+}
+
+#[test]
+fn events_resolve_oldest_first_on_each_board() {
+    let db = common::cards();
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let mut own = common::field(0, &[]);
+    let mut enemy = common::field(1, &[]);
+    let summon = |id: &str| {
+        monster(
+            1,
+            &format!(
+                "(trigger: Died, effect: (kind: Summon(\"{}\"), target: Hero(Friendly)))",
+                id
+            ),
+        )
+    };
+    own.summon(0, summon("wisp"));
+    own.summon(1, summon("orc_grunt"));
+    own.damage_monster(1, 1);
+    own.damage_monster(0, 1);
+    triggers::resolve_pending(&mut own, &mut enemy, &db, &mut rng);
+
+    assert_eq!(
+        names(&own)[..2],
+        [Some("Wisp".to_string()), Some("Orc Grunt".to_string())]
+    );
+}
+
+#[test]
+fn the_active_board_resolves_before_the_other() {
+    let db = common::cards();
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let mut own = common::field(0, &[]);
+    let mut enemy = common::field(1, &[]);
+    own.summon(
+        0,
+        monster(
+            1,
+            "(trigger: Died, effect: (kind: Summon(\"wisp\"), target: Hero(Enemy)))",
+        ),
+    );
+    enemy.summon(
+        0,
+        monster(
+            1,
+            "(trigger: Died, effect: (kind: Summon(\"orc_grunt\"), target: Hero(Friendly)))",
+        ),
+    );
+    enemy.damage_monster(0, 1);
+    own.damage_monster(0, 1);
+    triggers::resolve_pending(&mut own, &mut enemy, &db, &mut rng);
+
+    assert_eq!(
+        names(&enemy)[..2],
+        [Some("Wisp".to_string()), Some("Orc Grunt".to_string())]
+    );
+}
+
+#[test]
+fn phase_abilities_go_off_left_to_right() {
+    let db = common::cards();
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let mut own = common::field(0, &[]);
+    let mut enemy = common::field(1, &[]);
+    let summon = |id: &str| {
+        monster(
+            1,
+            &format!(
+                "(trigger: Phase(PrePlay), effect: (kind: Summon(\"{}\"), target: Hero(Friendly)))",
+                id
+            ),
+        )
+    };
+    own.summon(0, summon("wisp"));
+    own.summon(1, summon("orc_grunt"));
+
+    triggers::phase(TurnPhase::PostPlay, &mut own, &mut enemy, &db, &mut rng);
+    assert_eq!(own.first_free_slot(), Some(2));
+    triggers::phase(TurnPhase::PrePlay, &mut own, &mut enemy, &db, &mut rng);
+    assert_eq!(
+        names(&own)[2..4],
+        [Some("Wisp".to_string()), Some("Orc Grunt".to_string())]
+    );
+}
+
+#[test]
+fn abilities_setting_each_other_off_stop_at_the_cap() {
+    let db = common::cards();
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let mut own = common::field(0, &[]);
+    let mut enemy = common::field(1, &[]);
+    let echo = || {
+        monster(
+            1000,
+            "(trigger: Damaged, effect: (kind: DealDamage(1), target: AllMonsters(Enemy)))",
+        )
+    };
+    own.summon(0, echo());
+    enemy.summon(0, echo());
+    own.damage_monster(0, 1);
+    triggers::resolve_pending(&mut own, &mut enemy, &db, &mut rng);
+
+    let taken = 2000 - own.monster(0).unwrap().health() - enemy.monster(0).unwrap().health();
+    assert_eq!(taken as usize, 1 + MAX_TRIGGERS);
+}
+
+#[test]
+fn lingering_magic_goes_off_after_the_monsters_oldest_first() {
+    let db = common::cards();
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let mut own = common::field(0, &[]);
+    let mut enemy = common::field(1, &[]);
+    let summon = |id: &str| {
+        format!(
+            "(trigger: Phase(PrePlay), effect: (kind: Summon(\"{}\"), target: Hero(Friendly)))",
+            id
+        )
+    };
+    own.settle_magic(lingering(2, &summon("orc_grunt")));
+    own.settle_magic(lingering(2, &summon("ogre_brute")));
+    own.summon(0, monster(1, &summon("wisp")));
+
+    triggers::phase(TurnPhase::PrePlay, &mut own, &mut enemy, &db, &mut rng);
+    assert_eq!(
+        names(&own)[1..4],
+        [
+            Some("Wisp".to_string()),
+            Some("Orc Grunt".to_string()),
+            Some("Ogre Brute".to_string())
+        ]
+    );
+}
+
+#[test]
+fn lingering_magic_goes_to_the_graveyard_when_its_turns_are_up() {
+    let db = common::cards();
+    let mut board = common::field(0, &[]);
+    let magic = |id: &str| match db.card(id, 0) {
+        Some(CardHolder::MagicCard(magic)) => magic,
+        _ => panic!("{} is a magic card", id),
+    };
+    board.settle_magic(magic("fireball"));
+    board.settle_magic(magic("blizzard"));
+    assert_eq!(board.lingering().len(), 1);
+    assert_eq!(board.graveyard().len(), 1);
+
+    for turns_left in (1..3).rev() {
+        board.age_lingering();
+        assert_eq!(board.lingering()[0].turns_left(), turns_left);
+    }
+    board.age_lingering();
+    assert!(board.lingering().is_empty());
+    let buried: Vec<String> = board.graveyard().iter().map(|c| c.name()).collect();
+    assert_eq!(buried, vec!["Fireball", "Blizzard"]);
+}
+
+#[test]
+fn a_blizzard_keeps_hitting_at_the_start_of_its_owners_turns() {
+    let mut ecs = common::match_with(
+        &["wisp", "blizzard", "wisp", "wisp", "wisp", "wisp", "wisp"],
+        &["wisp"; 10],
+    );
+    let db = common::cards();
+    common::fill_mana(&ecs, 0);
+    common::with_board(&ecs, 1, |b| {
+        b.summon(0, db.monster("ogre_brute").unwrap().clone())
+    });
+    let enemy_health = |ecs: &World| common::with_board(ecs, 1, |b| b.monster(0).unwrap().health());
+
+    let card = common::hand_index(&ecs, 0, "Blizzard");
+    common::perform(&mut ecs, 0, Action::PlayCard { card, target: None });
+    common::perform(&mut ecs, 1, Action::Pass);
+    let mut health = vec![enemy_health(&ecs)];
+    for _ in 0..3 {
+        common::perform(&mut ecs, 0, Action::EndTurn);
+        match_cycle::advance(&mut ecs);
+        common::perform(&mut ecs, 1, Action::EndTurn);
+        match_cycle::advance(&mut ecs);
+        health.push(enemy_health(&ecs));
+    }
+    assert_eq!(health, vec![5, 4, 3, 3]);
+    common::with_board(&ecs, 0, |b| {
+        assert!(b.lingering().is_empty());
+        assert!(b.graveyard().iter().any(|c| c.name() == "Blizzard"));
+    });
+}
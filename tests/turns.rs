@@ -0,0 +1,74 @@
+mod common;
+
+use specs::prelude::*;
+use tcg_project::actions::{self, Action, ActionError};
+use tcg_project::entities::{Turn, TurnPhase};
+use tcg_project::match_cycle;
+
+fn turn(ecs: &World) -> (u32, u8, TurnPhase) {
+    let turn = *ecs.fetch::<Turn>();
+    (turn.number(), turn.player(), turn.phase())
+}
+
+fn hand_len(ecs: &World, player: u8) -> usize {
+    common::with_board(ecs, player, |b| b.hand().len())
+}
+
+#[test]
+fn turns_run_through_their_phases_and_alternate() {
+    let mut ecs = common::match_with(&["wisp"; 10], &["wisp"; 10]);
+    assert_eq!(turn(&ecs), (1, 0, TurnPhase::InPlay));
+    assert_eq!(hand_len(&ecs, 0), 7);
+    assert_eq!(hand_len(&ecs, 1), 6);
+
+    match_cycle::run_turn(&mut ecs);
+    assert_eq!(turn(&ecs), (1, 0, TurnPhase::InPlay));
+
+    common::perform(&mut ecs, 0, Action::EndTurn);
+    assert_eq!(turn(&ecs), (1, 0, TurnPhase::PostPlay));
+    match_cycle::run_turn(&mut ecs);
+    assert_eq!(turn(&ecs), (2, 1, TurnPhase::PrePlay));
+    assert_eq!(hand_len(&ecs, 1), 6);
+    match_cycle::run_turn(&mut ecs);
+    assert_eq!(turn(&ecs), (2, 1, TurnPhase::InPlay));
+    assert_eq!(hand_len(&ecs, 1), 7);
+
+    common::perform(&mut ecs, 1, Action::EndTurn);
+    match_cycle::advance(&mut ecs);
+    assert_eq!(turn(&ecs), (3, 0, TurnPhase::InPlay));
+    assert_eq!(hand_len(&ecs, 0), 8);
+}
+
+#[test]
+fn only_the_active_player_acts_and_only_in_play() {
+    let mut ecs = common::match_with(&["wisp"; 10], &["wisp"; 10]);
+    let wisp = Action::PlayCard {
+        card: 0,
+        target: None,
+    };
+    assert_eq!(match_cycle::acting_player(&ecs), Some(0));
+    assert_eq!(
+        actions::perform(&mut ecs, 1, wisp),
+        Err(ActionError::NotYourTurn)
+    );
+    assert_eq!(
+        match_cycle::end_turn(&mut ecs, 1),
+        Err(ActionError::NotYourTurn)
+    );
+    assert_eq!(hand_len(&ecs, 1), 6);
+
+    common::perform(&mut ecs, 0, Action::EndTurn);
+    assert_eq!(match_cycle::acting_player(&ecs), None);
+    for player in 0..2 {
+        assert_eq!(
+            actions::perform(&mut ecs, player, wisp),
+            Err(ActionError::NotYourTurn)
+        );
+    }
+    match_cycle::advance(&mut ecs);
+    assert_eq!(match_cycle::acting_player(&ecs), Some(1));
+    assert_eq!(
+        actions::perform(&mut ecs, 0, Action::EndTurn),
+        Err(ActionError::NotYourTurn)
+    );
+}
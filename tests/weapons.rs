@@ -0,0 +1,79 @@
+mod common;
+
+use tcg_project::actions::ActionError;
+use tcg_project::combat;
+use tcg_project::entities::{Board, Hero, HeroClass, TargetType, Weapon};
+use tcg_project::matchups::MatchupTable;
+
+fn armed(player: u8, weapon: &str, ids: &[Option<&str>]) -> Board {
+    let db = common::cards();
+    let mut board = common::field(player, ids);
+    board.equip(Weapon::new(
+        0,
+        db.weapon(weapon).expect("known weapon").clone(),
+    ));
+    board
+}
+
+#[test]
+fn armor_takes_damage_before_health() {
+    let mut hero = Hero::new(0, 30, 30, HeroClass::Warrior);
+    hero.gain_armor(3);
+    hero.take_damage(2);
+    assert_eq!((hero.armor(), hero.health()), (1, 30));
+    hero.take_damage(4);
+    assert_eq!((hero.armor(), hero.health()), (0, 27));
+}
+
+#[test]
+fn negative_damage_neither_heals_nor_touches_armor() {
+    let mut hero = Hero::new(0, 30, 30, HeroClass::Warrior);
+    hero.take_damage(5);
+    hero.gain_armor(2);
+    hero.take_damage(-3);
+    assert_eq!((hero.armor(), hero.health()), (2, 25));
+    hero.gain_armor(-2);
+    assert_eq!(hero.armor(), 2);
+}
+
+#[test]
+fn weapons_break_once_their_durability_runs_out() {
+    let table = MatchupTable::default();
+    let mut own = armed(0, "iron_sword", &[]);
+    let mut enemy = common::field(1, &[]);
+
+    assert!(combat::hero_attack(&mut own, &mut enemy, TargetType::Hero, &table).is_ok());
+    assert_eq!(own.hero().weapon().unwrap().durability(), 1);
+    assert_eq!(
+        combat::hero_attack(&mut own, &mut enemy, TargetType::Hero, &table),
+        Err(ActionError::HeroCannotAttack)
+    );
+
+    own.hero_mut().ready();
+    assert!(combat::hero_attack(&mut own, &mut enemy, TargetType::Hero, &table).is_ok());
+    assert_eq!(enemy.hero().health(), 26);
+    assert!(own.hero().weapon().is_none());
+    assert_eq!(own.graveyard()[0].name(), "Iron Sword");
+
+    own.hero_mut().ready();
+    assert_eq!(
+        combat::hero_attack(&mut own, &mut enemy, TargetType::Hero, &table),
+        Err(ActionError::NoWeapon)
+    );
+}
+
+#[test]
+fn monsters_strike_back_at_heroes_in_reach() {
+    let table = MatchupTable::default();
+    let mut own = armed(0, "iron_sword", &[]);
+    let mut enemy = common::field(1, &[Some("orc_grunt"), None, Some("orc_grunt")]);
+    own.hero_mut().gain_armor(2);
+
+    assert_eq!(
+        combat::hero_attack(&mut own, &mut enemy, TargetType::Monster(0), &table),
+        Err(ActionError::OutOfRange)
+    );
+    assert!(combat::hero_attack(&mut own, &mut enemy, TargetType::Monster(2), &table).is_ok());
+    assert_eq!(enemy.monster(2).unwrap().health(), 1);
+    assert_eq!((own.hero().armor(), own.hero().health()), (0, 29));
+}